@@ -0,0 +1,144 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attr::{patch_fields, Container, OnNull, PatchField};
+use crate::diff::field_diff;
+use crate::patch::checks;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = patch_fields(&input, "ApplyPatch")?;
+    let name = &input.ident;
+    let patch = format_ident!("{}Patch", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let checks = checks(&fields, quote!(patch));
+
+    let updates = fields.iter().map(|field| {
+        let ident = field.ident;
+        let ty = field.ty;
+
+        if field.optional && !field.merges() {
+            return quote!(patch.#ident.update_to(&mut self.#ident););
+        }
+
+        let on_null = if field.nullable {
+            quote!(self.#ident = ::core::option::Option::None)
+        } else {
+            match field.on_null {
+                OnNull::Error | OnNull::Ignore => quote!({}),
+                OnNull::Default => quote!(self.#ident = ::core::default::Default::default()),
+            }
+        };
+
+        let merge = |current: TokenStream| {
+            if field.nested.is_some() {
+                quote!(::nulls::ApplyPatch::apply(#current, value)?)
+            } else {
+                quote!(::nulls::patch::apply_map(#current, value))
+            }
+        };
+
+        let on_value = if !field.merges() {
+            quote!(self.#ident = value)
+        } else if field.optional {
+            let merge_some = merge(quote!(current));
+            let merge_none = merge(quote!(&mut current));
+
+            quote! {
+                match &mut self.#ident {
+                    ::core::option::Option::Some(current) => #merge_some,
+                    ::core::option::Option::None => {
+                        let mut current: #ty = ::core::default::Default::default();
+                        #merge_none;
+                        self.#ident = ::core::option::Option::Some(current);
+                    }
+                }
+            }
+        } else {
+            merge(quote!(&mut self.#ident))
+        };
+
+        quote! {
+            match patch.#ident {
+                ::nulls::Null::Value(value) => #on_value,
+                ::nulls::Null::Null => #on_null,
+                ::nulls::Null::Undefined => {}
+            }
+        }
+    });
+
+    let container = Container::parse(&input.attrs)?;
+
+    let inverse = if container.inverse {
+        inverse(&input, &container, &fields)
+    } else {
+        TokenStream::new()
+    };
+
+    Ok(quote! {
+        #inverse
+
+        impl #impl_generics ::nulls::ApplyPatch for #name #ty_generics #where_clause {
+            type Patch = #patch #ty_generics;
+
+            fn apply(&mut self, patch: Self::Patch) -> ::core::result::Result<(), ::nulls::PatchError> {
+                <Self as ::nulls::ApplyPatch>::validate(&patch)?;
+                #(#updates)*
+                ::core::result::Result::Ok(())
+            }
+
+            fn validate(patch: &Self::Patch) -> ::core::result::Result<(), ::nulls::PatchError> {
+                #(#checks)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Implements `nulls::patch::ApplyWithInverse` for `#[null(inverse)]`.
+fn inverse(input: &DeriveInput, container: &Container, fields: &[PatchField]) -> TokenStream {
+    let name = &input.ident;
+    let patch = format_ident!("{}Patch", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let overflow_init = container.overflow_init();
+    let idents: Vec<_> = fields.iter().map(|field| field.ident).collect();
+    let olds: Vec<_> = idents.iter().map(|ident| format_ident!("__old_{}", ident)).collect();
+
+    let inverses = fields.iter().zip(&olds).map(|(field, old)| {
+        let ident = field.ident;
+        let change = field_diff(field, quote!(&self.#ident), quote!(&#old));
+
+        quote! {
+            #ident: match #old {
+                ::core::option::Option::Some(#old) => #change,
+                ::core::option::Option::None => ::nulls::Null::Undefined,
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::nulls::patch::ApplyWithInverse for #name #ty_generics #where_clause {
+            fn apply_with_inverse(
+                &mut self,
+                patch: <Self as ::nulls::ApplyPatch>::Patch,
+            ) -> ::core::result::Result<<Self as ::nulls::ApplyPatch>::Patch, ::nulls::PatchError> {
+                #(
+                    let #olds = if patch.#idents.is_undefined() {
+                        ::core::option::Option::None
+                    } else {
+                        ::core::option::Option::Some(::core::clone::Clone::clone(&self.#idents))
+                    };
+                )*
+
+                ::nulls::ApplyPatch::apply(self, patch)?;
+
+                ::core::result::Result::Ok(#patch {
+                    #(#inverses,)*
+                    #overflow_init
+                })
+            }
+        }
+    }
+}
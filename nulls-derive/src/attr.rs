@@ -0,0 +1,254 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Attribute, DeriveInput, Ident, LitStr, Path, Type};
+
+use crate::util::{map_patch_type, named_fields, option_inner, patch_type};
+
+/// What `Null` does to a field that is not an `Option`.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum OnNull {
+    /// Reject the whole patch with `PatchError::Null`.
+    Error,
+    /// Leave the field as it is, like `Undefined`.
+    Ignore,
+    /// Reset the field to `Default::default()`.
+    Default,
+}
+
+/// `#[null(...)]` options accepted on the struct itself.
+#[derive(Default)]
+pub struct Container {
+    pub on_null: Option<OnNull>,
+    pub schema: bool,
+    pub inverse: bool,
+    pub avro: bool,
+    /// The name of the extra patch field collecting unknown members.
+    pub overflow: Option<Ident>,
+    /// The traits the patch derives, from `#[null(derive(...))]` or `#[null(no_derive)]`,
+    /// in place of the defaults.
+    pub derives: Option<Vec<Path>>,
+}
+
+impl Container {
+    /// The initializer for the overflow field in a patch struct literal, if there is one.
+    pub fn overflow_init(&self) -> Option<TokenStream> {
+        self.overflow.as_ref().map(|ident| quote!(#ident: ::nulls::Null::Undefined,))
+    }
+
+    /// Whether the patch derives the trait named `name`, such as `Clone` or `Serialize`.
+    pub fn derives(&self, name: &str) -> bool {
+        self.derives
+            .as_ref()
+            .is_none_or(|derives| derives.iter().any(|path| path.segments.last().is_some_and(|segment| segment.ident == name)))
+    }
+
+    /// The traits the patch derives other than serde's, which are resolved through
+    /// `nulls` instead.
+    pub fn std_derives(&self) -> Vec<Path> {
+        match &self.derives {
+            Some(derives) => derives
+                .iter()
+                .filter(|path| !path.segments.last().is_some_and(|segment| segment.ident == "Serialize" || segment.ident == "Deserialize"))
+                .cloned()
+                .collect(),
+            None => vec![syn::parse_quote!(Clone), syn::parse_quote!(Debug), syn::parse_quote!(PartialEq)],
+        }
+    }
+}
+
+/// `#[null(...)]` options accepted on a field.
+#[derive(Default)]
+pub struct Field {
+    pub on_null: Option<OnNull>,
+    pub required: bool,
+    pub non_nullable: bool,
+    pub rename: Option<String>,
+    pub aliases: Vec<String>,
+    pub skip: bool,
+    pub nested: bool,
+    pub map: bool,
+}
+
+/// A field that takes part in the patch, with its attributes resolved against the
+/// container's.
+pub struct PatchField<'a> {
+    pub field: &'a syn::Field,
+    pub ident: &'a Ident,
+    /// The name used in errors, field lists and serialized patches.
+    pub name: String,
+    /// Older names still accepted when deserializing or resolving paths.
+    #[cfg(feature = "serde")]
+    pub aliases: Vec<String>,
+    /// Whether the field is an `Option`, so its patch holds the inner type.
+    pub optional: bool,
+    /// Whether `Null` clears the field: it is an `Option` not marked `non_nullable`.
+    pub nullable: bool,
+    pub required: bool,
+    pub on_null: OnNull,
+    /// The field type, or `T` for an `Option<T>` field.
+    pub ty: &'a Type,
+    /// For `#[null(nested)]` fields, the patch type of `ty`, which is applied recursively
+    /// instead of replacing the whole value.
+    pub nested: Option<Type>,
+    /// For `#[null(map)]` fields, `ty` with its values wrapped in `Null`, which is applied
+    /// key by key with `nulls::patch::apply_map`.
+    pub map: Option<Type>,
+}
+
+impl PatchField<'_> {
+    /// The type inside the `Null` of the generated patch field.
+    pub fn patch_ty(&self) -> &Type {
+        self.nested.as_ref().or(self.map.as_ref()).unwrap_or(self.ty)
+    }
+
+    /// Whether `Value` merges into the current value instead of replacing it.
+    pub fn merges(&self) -> bool {
+        self.nested.is_some() || self.map.is_some()
+    }
+}
+
+impl Container {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container = Container::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("null")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("on_null") {
+                    container.on_null = Some(parse_on_null(&meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("schema") {
+                    container.schema = true;
+                } else if meta.path.is_ident("inverse") {
+                    container.inverse = true;
+                } else if meta.path.is_ident("avro") {
+                    container.avro = true;
+                } else if meta.path.is_ident("overflow") {
+                    container.overflow = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("derive") || meta.path.is_ident("no_derive") {
+                    if container.derives.is_some() {
+                        return Err(meta.error("the patch derives are already set"));
+                    }
+
+                    let mut derives = Vec::new();
+
+                    if meta.path.is_ident("derive") {
+                        meta.parse_nested_meta(|derive| {
+                            derives.push(derive.path);
+                            Ok(())
+                        })?;
+                    }
+
+                    container.derives = Some(derives);
+                } else {
+                    return Err(meta.error("unknown nulls container attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(container)
+    }
+}
+
+impl Field {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field = Field::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("null")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("on_null") {
+                    field.on_null = Some(parse_on_null(&meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("required") {
+                    field.required = true;
+                } else if meta.path.is_ident("non_nullable") {
+                    field.non_nullable = true;
+                } else if meta.path.is_ident("rename") {
+                    field.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("alias") {
+                    field.aliases.push(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("skip") {
+                    field.skip = true;
+                } else if meta.path.is_ident("nested") {
+                    field.nested = true;
+                } else if meta.path.is_ident("map") {
+                    field.map = true;
+                } else {
+                    return Err(meta.error("unknown nulls field attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(field)
+    }
+}
+
+/// Parses the container and field attributes, leaving out `#[null(skip)]` fields.
+pub fn patch_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<Vec<PatchField<'a>>> {
+    let container = Container::parse(&input.attrs)?;
+    let mut fields = Vec::new();
+
+    for field in named_fields(input, derive)? {
+        let attr = Field::parse(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named field");
+        let inner = option_inner(&field.ty);
+        let optional = inner.is_some();
+        let ty = inner.unwrap_or(&field.ty);
+
+        if attr.skip {
+            if attr.required || attr.non_nullable || attr.nested || attr.map || attr.rename.is_some() || !attr.aliases.is_empty() || attr.on_null.is_some() {
+                return Err(syn::Error::new_spanned(field, "`skip` cannot be combined with other nulls attributes"));
+            }
+
+            continue;
+        }
+
+        if attr.on_null.is_some() && (optional || attr.non_nullable) {
+            let message = if optional {
+                "`on_null` has no effect on `Option` fields"
+            } else {
+                "`on_null` conflicts with `non_nullable`"
+            };
+
+            return Err(syn::Error::new_spanned(field, message));
+        }
+
+        if attr.nested && attr.map {
+            return Err(syn::Error::new_spanned(field, "`nested` conflicts with `map`"));
+        }
+
+        let on_null = if attr.non_nullable {
+            OnNull::Error
+        } else {
+            attr.on_null.or(container.on_null).unwrap_or(OnNull::Error)
+        };
+
+        fields.push(PatchField {
+            field,
+            ident,
+            name: attr.rename.unwrap_or_else(|| ident.unraw().to_string()),
+            #[cfg(feature = "serde")]
+            aliases: attr.aliases,
+            optional,
+            nullable: optional && !attr.non_nullable,
+            required: attr.required,
+            on_null,
+            ty,
+            nested: if attr.nested { Some(patch_type(ty)?) } else { None },
+            map: if attr.map { Some(map_patch_type(ty)?) } else { None },
+        });
+    }
+
+    Ok(fields)
+}
+
+fn parse_on_null(lit: &LitStr) -> syn::Result<OnNull> {
+    match lit.value().as_str() {
+        "error" => Ok(OnNull::Error),
+        "ignore" => Ok(OnNull::Ignore),
+        "default" => Ok(OnNull::Default),
+        _ => Err(syn::Error::new_spanned(lit, r#"expected one of "error", "ignore" or "default""#)),
+    }
+}
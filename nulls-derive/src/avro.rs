@@ -0,0 +1,74 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::DeriveInput;
+
+use crate::attr::PatchField;
+use crate::schema::docs;
+
+/// Implements `AvroSchemaComponent` for `{Name}Patch`, a record of `["null", T]` unions.
+pub fn expand(input: &DeriveInput, fields: &[PatchField]) -> syn::Result<TokenStream> {
+    let patch = format_ident!("{}Patch", input.ident);
+
+    check_name(&patch.to_string(), &input.ident)?;
+
+    for field in fields {
+        for name in std::iter::once(&field.name).chain(&field.aliases) {
+            check_name(name, field.field)?;
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let record = patch.to_string();
+    let avro = quote!(::nulls::avro::__apache_avro);
+
+    let types: Vec<_> = fields.iter().map(|field| field.patch_ty()).collect();
+    let mut bounds = where_clause.map_or_else(|| quote!(where), |clause| quote!(#clause,));
+    bounds.extend(quote!(#(::nulls::Null<#types>: #avro::schema::derive::AvroSchemaComponent,)*));
+
+    let record_fields = fields.iter().zip(&types).map(|(field, ty)| {
+        let name = &field.name;
+        let aliases = &field.aliases;
+        let doc = match docs(field) {
+            Some(doc) => quote!(::core::option::Option::Some(#doc)),
+            None => quote!(::core::option::Option::None),
+        };
+
+        quote! {
+            ::nulls::avro::__field(
+                #name,
+                &[#(#aliases),*],
+                #doc,
+                <::nulls::Null<#ty> as #avro::schema::derive::AvroSchemaComponent>::get_schema_in_ctxt(
+                    named_schemas,
+                    enclosing_namespace,
+                ),
+            )
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #avro::schema::derive::AvroSchemaComponent for #patch #ty_generics #bounds {
+            fn get_schema_in_ctxt(
+                named_schemas: &mut ::std::collections::HashMap<#avro::schema::Name, #avro::schema::Schema>,
+                enclosing_namespace: &#avro::schema::Namespace,
+            ) -> #avro::schema::Schema {
+                ::nulls::avro::__record(#record, named_schemas, enclosing_namespace, |named_schemas, enclosing_namespace| {
+                    ::std::vec![#(#record_fields),*]
+                })
+            }
+        }
+    })
+}
+
+/// Rejects a record, field or alias name that Avro would not accept: a letter or `_`,
+/// followed by letters, digits and `_`.
+fn check_name(name: &str, tokens: impl ToTokens) -> syn::Result<()> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(tokens, format!("`{}` is not a valid Avro name", name)))
+    }
+}
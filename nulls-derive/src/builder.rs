@@ -0,0 +1,74 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attr::{patch_fields, Container, OnNull};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = patch_fields(&input, "NullBuilder")?;
+    let container = Container::parse(&input.attrs)?;
+    let vis = &input.vis;
+    let name = &input.ident;
+    let patch = format_ident!("{}Patch", name);
+    let builder = format_ident!("{}PatchBuilder", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+
+    let setters = fields.iter().map(|field| {
+        let vis = &field.field.vis;
+        let ident = field.ident;
+        let ty = field.patch_ty();
+        let clear = format_ident!("clear_{}", ident);
+
+        let set_doc = format!("Sets `{}` to `Value`.", field.name);
+        let clear_doc = format!("Sets `{}` to `Null`.", field.name);
+
+        let clear = (field.nullable || field.on_null != OnNull::Error).then(|| {
+            quote! {
+                #[doc = #clear_doc]
+                #vis fn #clear(mut self) -> Self {
+                    self.patch.#ident = ::nulls::Null::Null;
+                    self
+                }
+            }
+        });
+
+        quote! {
+            #[doc = #set_doc]
+            #vis fn #ident(mut self, value: impl ::core::convert::Into<#ty>) -> Self {
+                self.patch.#ident = ::nulls::Null::Value(::core::convert::Into::into(value));
+                self
+            }
+
+            #clear
+        }
+    });
+
+    let doc = format!("Builder for [`{}`], generated by `#[derive(NullBuilder)]`.", patch);
+    let derives = ["Clone", "Debug"].into_iter().filter(|name| container.derives(name)).map(|name| format_ident!("{}", name));
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(#(#derives),*)]
+        #vis struct #builder #generics #where_clause {
+            patch: #patch #ty_generics,
+        }
+
+        impl #impl_generics #patch #ty_generics #where_clause {
+            /// Starts a builder with every field `Undefined`.
+            #vis fn builder() -> #builder #ty_generics {
+                #builder {
+                    patch: ::core::default::Default::default(),
+                }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            #vis fn build(self) -> #patch #ty_generics {
+                self.patch
+            }
+        }
+    })
+}
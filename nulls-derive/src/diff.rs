@@ -0,0 +1,91 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attr::{patch_fields, Container, PatchField};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = patch_fields(&input, "Diff")?;
+    let overflow_init = Container::parse(&input.attrs)?.overflow_init();
+    let name = &input.ident;
+    let patch = format_ident!("{}Patch", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let changes = fields.iter().map(|field| {
+        let ident = field.ident;
+        let change = field_diff(field, quote!(&old.#ident), quote!(&new.#ident));
+
+        quote!(#ident: #change)
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::nulls::Diff for #name #ty_generics #where_clause {
+            type Patch = #patch #ty_generics;
+
+            fn diff(old: &Self, new: &Self) -> Self::Patch {
+                #patch {
+                    #(#changes,)*
+                    #overflow_init
+                }
+            }
+        }
+    })
+}
+
+/// The patch value turning the field value `old` into `new`, both references.
+pub fn field_diff(field: &PatchField, old: TokenStream, new: TokenStream) -> TokenStream {
+    let ty = field.ty;
+
+    let diff = |old: TokenStream, new: TokenStream| {
+        if field.nested.is_some() {
+            quote!(<#ty as ::nulls::Diff>::diff(#old, #new))
+        } else {
+            quote!(::nulls::patch::diff_map(#old, #new))
+        }
+    };
+
+    let change = if field.merges() && field.optional {
+        let diff_some = diff(quote!(old), quote!(new));
+        let diff_none = diff(quote!(&<#ty as ::core::default::Default>::default()), quote!(new));
+
+        quote! {
+            match (old, new) {
+                (old, new) if old == new => ::nulls::Null::Undefined,
+                (_, ::core::option::Option::None) => ::nulls::Null::Null,
+                (::core::option::Option::Some(old), ::core::option::Option::Some(new)) => {
+                    ::nulls::Null::Value(#diff_some)
+                }
+                (::core::option::Option::None, ::core::option::Option::Some(new)) => {
+                    ::nulls::Null::Value(#diff_none)
+                }
+            }
+        }
+    } else if field.merges() {
+        let diff = diff(quote!(old), quote!(new));
+
+        quote! {
+            if old == new {
+                ::nulls::Null::Undefined
+            } else {
+                ::nulls::Null::Value(#diff)
+            }
+        }
+    } else if field.optional {
+        quote!(::nulls::Null::from_change(old, new))
+    } else {
+        quote! {
+            if old == new {
+                ::nulls::Null::Undefined
+            } else {
+                ::nulls::Null::Value(::core::clone::Clone::clone(new))
+            }
+        }
+    };
+
+    quote! {
+        {
+            let (old, new) = (#old, #new);
+            #change
+        }
+    }
+}
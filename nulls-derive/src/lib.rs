@@ -0,0 +1,545 @@
+//! Derive macros for [`nulls`](https://docs.rs/nulls). Use them through the `derive`
+//! feature of `nulls` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod apply;
+mod attr;
+#[cfg(feature = "avro")]
+mod avro;
+mod builder;
+mod diff;
+mod patch;
+mod record;
+#[cfg(feature = "serde")]
+mod schema;
+#[cfg(feature = "serde")]
+mod skip;
+mod track;
+mod util;
+
+/// Generates a `{Name}Patch` struct with every field wrapped in `Null`.
+///
+/// `Option<T>` fields become `Null<T>` and any other `T` becomes `Null<T>`. The patch
+/// implements `Default` (every field `Undefined`) and derives `Clone`, `Debug` and
+/// `PartialEq`, and, with the `serde` feature of `nulls`, `Serialize`/`Deserialize` with
+/// missing fields read as `Undefined` and `Undefined` fields left out of the output. See
+/// [Derives](#derives) for field types that lack these traits.
+///
+/// The patch also gets `defined_fields` and `undefined_fields`, listing field names in
+/// declaration order, plus `is_empty` (every field `Undefined`) and `is_complete` (no
+/// field `Undefined`).
+///
+/// # Field attributes
+///
+/// These are read by every derive in this crate, so they only need to be written once:
+///
+/// - `#[null(required)]`: `validate` and `apply` reject the patch with
+///   `PatchError::Missing` when the field is `Undefined`.
+/// - `#[null(non_nullable)]`: the field may be updated but never cleared, so `Null` is
+///   rejected with `PatchError::Null`, even on an `Option` field.
+/// - `#[null(rename = "...")]`: the name used in errors, in the field lists and by serde.
+/// - `#[null(alias = "...")]`: an older name still accepted when deserializing and in
+///   field paths. It may be repeated.
+/// - `#[null(skip)]`: the field is left out of the patch, and `apply` and `diff` never
+///   touch it.
+/// - `#[null(nested)]`: the patch holds the field type's own patch (`Null<AddressPatch>`
+///   for an `Address` field) and `apply` and `diff` recurse into it, instead of replacing
+///   the whole value. The field type must derive the same traits. A nested `Option` that
+///   is `None` is patched starting from `Default::default()`.
+/// - `#[null(map)]`: for a `HashMap<K, V>` or `BTreeMap<K, V>` field, the patch holds the
+///   same map type over `Null<V>` (`BTreeMap<K, Null<V>>` for a `BTreeMap` field), which
+///   `apply` merges key by key with `nulls::patch::apply_map`: `Value` entries are upserted,
+///   `Null` entries removed and other keys kept.
+///
+/// ```
+/// use nulls::{Null, Patch, PatchError};
+///
+/// #[derive(Patch)]
+/// struct Account {
+///     #[null(skip)]
+///     id: u64,
+///     #[null(required, rename = "emailAddress")]
+///     email: String,
+///     #[null(non_nullable)]
+///     nickname: Option<String>,
+/// }
+///
+/// let patch: AccountPatch = serde_json::from_str(r#"{"nickname":null}"#).unwrap();
+/// assert_eq!(patch.validate(), Err(PatchError::Missing("emailAddress")));
+///
+/// let patch: AccountPatch = serde_json::from_str(r#"{"emailAddress":"a@b.c","nickname":null}"#).unwrap();
+/// assert_eq!(patch.validate(), Err(PatchError::Null("nickname")));
+/// ```
+///
+/// Nested patches merge into the current value:
+///
+/// ```
+/// use nulls::{patch, ApplyPatch, Diff, Patch};
+///
+/// #[derive(Patch, ApplyPatch, Diff, Clone, PartialEq, Debug)]
+/// struct Address {
+///     city: String,
+///     zip: Option<String>,
+/// }
+///
+/// #[derive(Patch, ApplyPatch, Diff)]
+/// struct User {
+///     name: String,
+///     #[null(nested)]
+///     address: Address,
+/// }
+///
+/// let mut user = User { name: "nulls".into(), address: Address { city: "Manila".into(), zip: Some("1000".into()) } };
+/// let old = user.address.clone();
+///
+/// user.apply(patch!(UserPatch { address: patch!(AddressPatch { zip: null }) })).unwrap();
+/// assert_eq!(user.address, Address { city: "Manila".into(), zip: None });
+///
+/// let patch = Address::diff(&old, &user.address);
+/// assert_eq!(patch, patch!(AddressPatch { zip: null }));
+/// ```
+///
+/// Map patches keep the field's map type:
+///
+/// ```
+/// use nulls::{ApplyPatch, Null, Patch};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Patch, ApplyPatch)]
+/// struct Config {
+///     #[null(map)]
+///     labels: BTreeMap<String, String>,
+/// }
+///
+/// let mut config = Config { labels: BTreeMap::from([("env".into(), "dev".into()), ("team".into(), "core".into())]) };
+///
+/// let labels: BTreeMap<String, Null<String>> =
+///     BTreeMap::from([("env".into(), nulls::new("prod".into())), ("team".into(), Null::Null)]);
+/// config.apply(ConfigPatch { labels: nulls::new(labels) }).unwrap();
+/// assert_eq!(config.labels, BTreeMap::from([("env".into(), "prod".into())]));
+/// ```
+///
+/// ```
+/// use nulls::{Null, Patch};
+///
+/// #[derive(Patch)]
+/// pub struct User {
+///     pub name: String,
+///     pub bio: Option<String>,
+/// }
+///
+/// let patch: UserPatch = serde_json::from_str(r#"{"bio":null}"#).unwrap();
+/// assert_eq!(patch, UserPatch { name: Null::Undefined, bio: Null::Null });
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"bio":null}"#);
+///
+/// assert_eq!(patch.defined_fields(), ["bio"]);
+/// assert_eq!(patch.undefined_fields(), ["name"]);
+/// assert!(!patch.is_empty() && !patch.is_complete());
+/// assert!(UserPatch::default().is_empty());
+/// ```
+///
+/// # Derives
+///
+/// `#[null(derive(...))]` on the struct replaces the derived traits with the ones listed,
+/// and `#[null(no_derive)]` derives none; `Default` is always implemented. `Serialize` and
+/// `Deserialize` in the list are serde's, with the field attributes above; field paths
+/// need `Deserialize`. A `NullBuilder` builder derives `Clone` and `Debug` only when the
+/// patch does.
+///
+/// ```
+/// use nulls::{ApplyPatch, Null, Patch};
+///
+/// struct Handle(u32);
+///
+/// #[derive(Patch, ApplyPatch)]
+/// #[null(no_derive)]
+/// struct Connection {
+///     name: String,
+///     handle: Handle,
+/// }
+///
+/// #[derive(Clone, serde::Serialize)]
+/// struct Port(u16);
+///
+/// #[derive(Patch)]
+/// #[null(derive(Clone, serde::Serialize))]
+/// struct Listener {
+///     port: Port,
+/// }
+///
+/// let mut connection = Connection { name: "db".into(), handle: Handle(1) };
+/// connection.apply(ConnectionPatch { handle: nulls::new(Handle(2)), ..Default::default() }).unwrap();
+/// assert_eq!(connection.handle.0, 2);
+///
+/// let patch = ListenerPatch { port: nulls::new(Port(80)) };
+/// assert_eq!(serde_json::to_string(&patch.clone()).unwrap(), r#"{"port":80}"#);
+/// ```
+///
+/// # Schema evolution
+///
+/// Stored patches outlive the code that wrote them. Besides `alias` for renamed fields,
+/// `#[null(overflow = "extra")]` on the struct adds an `extra` field to the patch (with the
+/// `serde` feature of `nulls`) that collects members matching no field, using
+/// `nulls::serde::overflow`. It is `Undefined` when there are none, is written back when
+/// the patch is serialized, and is ignored by `apply`, the field lists and the other
+/// generated methods.
+///
+/// ```
+/// use nulls::{Null, Patch};
+///
+/// #[derive(Patch)]
+/// #[null(overflow = "extra")]
+/// struct User {
+///     #[null(alias = "displayName")]
+///     name: String,
+/// }
+///
+/// let patch: UserPatch = serde_json::from_str(r#"{"displayName":"nulls","nick":"n"}"#).unwrap();
+/// assert_eq!(patch.name, nulls::new("nulls".into()));
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"name":"nulls","nick":"n"}"#);
+///
+/// let patch: UserPatch = serde_json::from_str(r#"{"name":"nulls"}"#).unwrap();
+/// assert!(patch.extra.is_undefined());
+/// ```
+///
+/// # Field paths
+///
+/// With the `serde` feature of `nulls`, the patch also implements `nulls::path::Reflect`,
+/// so it can be built from dotted paths at runtime. Paths use the serialized field names
+/// and continue into `nested` fields.
+///
+///
+/// ```
+/// use nulls::path::{PatchOps, PathError};
+/// use nulls::{ApplyPatch, Null, Patch};
+///
+/// #[derive(Patch, ApplyPatch)]
+/// struct Profile {
+///     name: String,
+///     bio: Option<String>,
+/// }
+///
+/// #[derive(Patch)]
+/// struct User {
+///     email: String,
+///     #[null(nested)]
+///     profile: Profile,
+/// }
+///
+/// let mut ops = PatchOps::new();
+/// ops.set("profile.name", "nulls").unwrap().clear("profile.bio");
+///
+/// let patch: UserPatch = ops.build().unwrap();
+/// assert_eq!(patch.email, Null::Undefined);
+/// assert_eq!(patch.profile, nulls::new(ProfilePatch { name: nulls::new("nulls".into()), bio: Null::Null }));
+///
+/// let mut ops = PatchOps::new();
+/// ops.clear("email");
+/// assert!(matches!(ops.build::<UserPatch>(), Err(PathError::NotNullable(path)) if path == "email"));
+/// ```
+///
+/// # Schema
+///
+/// With the `serde` feature of `nulls`, `#[null(schema)]` on the struct adds
+/// `{Name}Patch::schema()`, returning a JSON Schema (OpenAPI 3.1) for the patch as a
+/// `serde_json::Value`. Every field is optional unless it is `required`, and fields that
+/// accept `Null` allow `null`. Common std types get a precise schema, `nested` fields use
+/// the nested patch's own `schema()` (so the nested type needs `#[null(schema)]` too) and
+/// any other type accepts anything. Doc comments become descriptions.
+///
+/// ```
+/// use nulls::Patch;
+/// use serde_json::json;
+///
+/// #[derive(Patch)]
+/// #[null(schema)]
+/// struct User {
+///     /// Display name.
+///     #[null(required)]
+///     name: String,
+///     tags: Option<Vec<String>>,
+/// }
+///
+/// assert_eq!(
+///     UserPatch::schema(),
+///     json!({
+///         "title": "UserPatch",
+///         "type": "object",
+///         "properties": {
+///             "name": { "type": "string", "description": "Display name." },
+///             "tags": { "anyOf": [{ "type": "array", "items": { "type": "string" } }, { "type": "null" }] },
+///         },
+///         "required": ["name"],
+///     }),
+/// );
+/// ```
+///
+/// # Avro
+///
+/// With the `apache-avro` feature of `nulls`, `#[null(avro)]` on the struct implements
+/// `AvroSchema` for the patch: a record named `{Name}Patch` whose fields are all
+/// `["null", T]` unions defaulting to `null`, so `Undefined` fields are written as `null`
+/// and older events missing a field still read. Renamed fields keep their name, aliases
+/// become Avro aliases and doc comments become field docs. `nested` fields need
+/// `#[null(avro)]` on the nested type too. Use [`nulls::avro::to_value`] to fill in the
+/// fields that serialization leaves out.
+///
+/// ```
+/// use apache_avro::{from_avro_datum, from_value, to_avro_datum, AvroSchema};
+/// use nulls::{Null, Patch};
+///
+/// #[derive(Patch)]
+/// #[null(avro)]
+/// struct User {
+///     #[null(rename = "displayName")]
+///     name: String,
+///     age: Option<i32>,
+/// }
+///
+/// let schema = UserPatch::get_schema();
+/// assert_eq!(
+///     schema.canonical_form(),
+///     r#"{"name":"UserPatch","type":"record","fields":[{"name":"displayName","type":["null","string"]},{"name":"age","type":["null","int"]}]}"#,
+/// );
+///
+/// let patch = UserPatch { name: nulls::new("nulls".into()), age: Null::Null };
+/// let bytes = to_avro_datum(&schema, nulls::avro::to_value(&patch).unwrap()).unwrap();
+/// let value = from_avro_datum(&schema, &mut bytes.as_slice(), None).unwrap();
+/// assert_eq!(from_value::<UserPatch>(&value).unwrap(), patch);
+/// ```
+#[proc_macro_derive(Patch, attributes(null))]
+pub fn derive_patch(input: TokenStream) -> TokenStream {
+    patch::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `nulls::ApplyPatch` using the `{Name}Patch` struct from `#[derive(Patch)]`.
+///
+/// Every field is updated with `Null::update_to` semantics: `Value` overwrites, `Null`
+/// clears an `Option` field and `Undefined` leaves the field alone. What `Null` means for
+/// a field that is not an `Option` is set with `#[null(on_null = "...")]`, on the struct or
+/// on the field:
+///
+/// - `"error"` (the default) rejects the patch with `PatchError::Null` before anything
+///   is written.
+/// - `"ignore"` leaves the field alone.
+/// - `"default"` resets the field to `Default::default()`.
+///
+/// ```
+/// use nulls::{ApplyPatch, Null, Patch, PatchError};
+///
+/// #[derive(Patch, ApplyPatch)]
+/// struct User {
+///     name: String,
+///     bio: Option<String>,
+///     #[null(on_null = "default")]
+///     tags: Vec<String>,
+/// }
+///
+/// let mut user = User { name: "nulls".into(), bio: Some("hi".into()), tags: vec!["a".into()] };
+///
+/// let patch = UserPatch { bio: Null::Null, tags: Null::Null, ..Default::default() };
+/// user.apply(patch).unwrap();
+/// assert_eq!((user.name.as_str(), user.bio.as_deref(), user.tags.len()), ("nulls", None, 0));
+///
+/// let patch = UserPatch { name: Null::Null, bio: nulls::new("new".into()), ..Default::default() };
+/// assert_eq!(user.apply(patch), Err(PatchError::Null("name")));
+/// assert_eq!(user.bio, None);
+/// ```
+///
+/// With `#[null(inverse)]` on the struct, it also implements
+/// `nulls::patch::ApplyWithInverse`, whose `apply_with_inverse` returns the patch undoing
+/// the changes, for undo stacks and compensating transactions. Fields touched by the patch
+/// are cloned before it is applied and compared afterwards, so their types must implement
+/// `Clone` and `PartialEq`, and `nested` field types must also derive `Diff`. An
+/// `Option` field marked `non_nullable` that went from `None` to `Some` gets a `Null`
+/// inverse, which that field rejects.
+///
+/// ```
+/// use nulls::patch::ApplyWithInverse;
+/// use nulls::{patch, ApplyPatch, Null, Patch};
+///
+/// #[derive(Patch, ApplyPatch, Clone, PartialEq, Debug)]
+/// #[null(inverse)]
+/// struct User {
+///     name: String,
+///     bio: Option<String>,
+///     age: u32,
+/// }
+///
+/// let mut user = User { name: "nulls".into(), bio: None, age: 1 };
+/// let before = user.clone();
+///
+/// let inverse = user.apply_with_inverse(patch!(UserPatch { name: "null", bio: "hi", age: 1u32 })).unwrap();
+/// assert_eq!(inverse, UserPatch { name: nulls::new("nulls".into()), bio: Null::Null, age: Null::Undefined });
+///
+/// user.apply(inverse).unwrap();
+/// assert_eq!(user, before);
+/// ```
+#[proc_macro_derive(ApplyPatch, attributes(null))]
+pub fn derive_apply_patch(input: TokenStream) -> TokenStream {
+    apply::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `nulls::Diff`, computing the `{Name}Patch` that turns one value into another.
+///
+/// Unchanged fields become `Undefined`, cleared `Option` fields become `Null` and changed
+/// fields become `Value`. Every field type must implement `Clone` and `PartialEq`.
+///
+/// ```
+/// use nulls::{Diff, Null, Patch};
+///
+/// #[derive(Patch, Diff)]
+/// struct User {
+///     name: String,
+///     email: String,
+///     bio: Option<String>,
+/// }
+///
+/// let old = User { name: "nulls".into(), email: "a@b.c".into(), bio: Some("hi".into()) };
+/// let new = User { name: "nulls".into(), email: "d@e.f".into(), bio: None };
+///
+/// let patch = User::diff(&old, &new);
+/// assert_eq!(patch, UserPatch { name: Null::Undefined, email: nulls::new("d@e.f".into()), bio: Null::Null });
+/// ```
+#[proc_macro_derive(Diff, attributes(null))]
+pub fn derive_diff(input: TokenStream) -> TokenStream {
+    diff::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `nulls::changeset::Record` for a type that also derives `ApplyPatch`,
+/// recording the old and new JSON value of every field the patch changed. Needs the
+/// `serde` feature of `nulls`, and every field must implement `Serialize`.
+///
+/// ```
+/// use nulls::changeset::Record;
+/// use nulls::{ApplyPatch, Null, Patch};
+/// use serde_json::json;
+///
+/// #[derive(Patch, ApplyPatch, Record)]
+/// struct User {
+///     name: String,
+///     bio: Option<String>,
+/// }
+///
+/// let mut user = User { name: "nulls".into(), bio: Some("hi".into()) };
+/// let patch = UserPatch { name: nulls::new("nulls".into()), bio: Null::Null };
+///
+/// let changes = user.apply_recorded(patch).unwrap();
+/// assert_eq!(
+///     serde_json::to_value(&changes).unwrap(),
+///     json!([{ "field": "bio", "old": "hi", "new": null }]),
+/// );
+/// ```
+#[proc_macro_derive(Record, attributes(null))]
+pub fn derive_record(input: TokenStream) -> TokenStream {
+    record::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a builder for the `{Name}Patch` struct from `#[derive(Patch)]`, started with
+/// `{Name}Patch::builder()`.
+///
+/// Each field gets a setter taking anything that converts into the field's type (or its
+/// patch type for `nested` and `map` fields) and setting it to `Value`. Fields that may be
+/// `Null` also get a `clear_*` method; fields that would reject `Null` have none. Fields
+/// that are not set stay `Undefined`.
+///
+/// ```
+/// use nulls::{Null, NullBuilder, Patch};
+///
+/// #[derive(Patch, NullBuilder)]
+/// struct User {
+///     name: String,
+///     bio: Option<String>,
+///     age: u32,
+/// }
+///
+/// let patch = UserPatch::builder().name("nulls").clear_bio().build();
+/// assert_eq!(patch, UserPatch { name: nulls::new("nulls".into()), bio: Null::Null, age: Null::Undefined });
+/// ```
+#[proc_macro_derive(NullBuilder, attributes(null))]
+pub fn derive_null_builder(input: TokenStream) -> TokenStream {
+    builder::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a `{Name}Tracker` wrapper that records changes made through its setters as a
+/// `{Name}Patch`, for dirty tracking. Start one with `value.track()`.
+///
+/// Each field gets `set_*`, recording `Value`, and `Option` fields that may be cleared also
+/// get `clear_*`, recording `Null`. Reading goes through `Deref`. `changes()` returns the
+/// patch so far, `take_changes()` also resets it, and `into_parts()` returns the value
+/// together with its changes. Field types must implement `Clone`. `nested` and `map`
+/// fields are not supported.
+///
+/// ```
+/// use nulls::{Null, Patch, TrackChanges};
+///
+/// #[derive(Patch, TrackChanges)]
+/// struct User {
+///     name: String,
+///     bio: Option<String>,
+///     age: u32,
+/// }
+///
+/// let mut user = User { name: "nulls".into(), bio: Some("hi".into()), age: 1 }.track();
+/// user.set_name("null").clear_bio();
+///
+/// assert_eq!(user.name, "null");
+/// assert!(user.is_dirty());
+/// assert_eq!(user.take_changes(), UserPatch { name: nulls::new("null".into()), bio: Null::Null, age: Null::Undefined });
+/// assert!(!user.is_dirty());
+/// ```
+#[proc_macro_derive(TrackChanges, attributes(null))]
+pub fn derive_track_changes(input: TokenStream) -> TokenStream {
+    track::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Adds `#[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]` to every
+/// `Null` field of a struct, so a missing field reads as `Undefined` and `Undefined` is
+/// left out of the output, without repeating both on each field. Options a field already
+/// sets itself are kept. Place it above `#[derive(Serialize, Deserialize)]`. Needs the
+/// `serde` feature of `nulls`.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[nulls::undefined_skip]
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Patch {
+///     name: Null<String>,
+///     #[serde(rename = "biography")]
+///     bio: Null<String>,
+///     id: u64,
+/// }
+///
+/// let patch: Patch = serde_json::from_str(r#"{"id":1,"biography":null}"#).unwrap();
+/// assert_eq!(patch, Patch { name: Null::Undefined, bio: Null::Null, id: 1 });
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"biography":null,"id":1}"#);
+/// ```
+#[cfg(feature = "serde")]
+#[proc_macro_attribute]
+pub fn undefined_skip(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(proc_macro2::Span::call_site(), "`undefined_skip` takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+
+    skip::expand(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,349 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attr::{patch_fields, Container, OnNull, PatchField};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = patch_fields(&input, "Patch")?;
+    let container = Container::parse(&input.attrs)?;
+    let vis = &input.vis;
+    let name = &input.ident;
+    let patch = format_ident!("{}Patch", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+
+    let definitions = fields.iter().map(|field| {
+        let vis = &field.field.vis;
+        let ident = field.ident;
+        let ty = field.patch_ty();
+        let docs = field.field.attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+
+        let serde = serde_field_attributes(&container, field);
+
+        quote! {
+            #(#docs)*
+            #serde
+            #vis #ident: ::nulls::Null<#ty>
+        }
+    });
+
+    let idents: Vec<_> = fields.iter().map(|field| field.ident).collect();
+    let names: Vec<_> = fields.iter().map(|field| &field.name).collect();
+    let checks = checks(&fields, quote!(self));
+
+    let reflect = reflect(&input, &container, &fields);
+    let schema = schema(&input, &container, &fields)?;
+    let avro = avro(&input, &container, &fields)?;
+    let overflow = overflow(&input, &container)?;
+    let overflow_init = container.overflow_init();
+
+    let doc = format!("Partial update for [`{}`], generated by `#[derive(Patch)]`.", name);
+    let derives = container.std_derives();
+    let serde = serde_container_attributes(&container);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(#(#derives),*)]
+        #serde
+        #vis struct #patch #generics #where_clause {
+            #(#definitions,)*
+            #overflow
+        }
+
+        impl #impl_generics ::core::default::Default for #patch #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#idents: ::nulls::Null::Undefined,)*
+                    #overflow_init
+                }
+            }
+        }
+
+        impl #impl_generics #patch #ty_generics #where_clause {
+            /// Names of the fields that are `Null` or `Value`, in declaration order.
+            #vis fn defined_fields(&self) -> ::std::vec::Vec<&'static str> {
+                let mut fields = ::std::vec::Vec::new();
+                #(
+                    if !self.#idents.is_undefined() {
+                        fields.push(#names);
+                    }
+                )*
+                fields
+            }
+
+            /// Names of the fields that are `Undefined`, in declaration order.
+            #vis fn undefined_fields(&self) -> ::std::vec::Vec<&'static str> {
+                let mut fields = ::std::vec::Vec::new();
+                #(
+                    if self.#idents.is_undefined() {
+                        fields.push(#names);
+                    }
+                )*
+                fields
+            }
+
+            /// Returns `true` if every field is `Undefined`, so applying the patch changes nothing.
+            #vis fn is_empty(&self) -> bool {
+                true #(&& self.#idents.is_undefined())*
+            }
+
+            /// Returns `true` if no field is `Undefined`.
+            #vis fn is_complete(&self) -> bool {
+                true #(&& !self.#idents.is_undefined())*
+            }
+
+            /// Checks the `#[null(...)]` rules: `required` fields must not be `Undefined`,
+            /// fields that cannot be cleared must not be `Null` and nested patches must be
+            /// valid themselves.
+            #vis fn validate(&self) -> ::core::result::Result<(), ::nulls::PatchError> {
+                #(#checks)*
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        #reflect
+
+        #schema
+
+        #avro
+    })
+}
+
+/// Early returns rejecting a patch, reached through `receiver`, that breaks the field rules.
+pub fn checks(fields: &[PatchField], receiver: TokenStream) -> Vec<TokenStream> {
+    let mut checks = Vec::new();
+
+    for field in fields {
+        let ident = field.ident;
+        let name = &field.name;
+
+        if field.required {
+            checks.push(quote! {
+                if #receiver.#ident.is_undefined() {
+                    return ::core::result::Result::Err(::nulls::PatchError::Missing(#name));
+                }
+            });
+        }
+
+        if !field.nullable && field.on_null == OnNull::Error {
+            checks.push(quote! {
+                if #receiver.#ident.is_null() {
+                    return ::core::result::Result::Err(::nulls::PatchError::Null(#name));
+                }
+            });
+        }
+
+        if field.nested.is_some() {
+            let ty = field.ty;
+
+            checks.push(quote! {
+                if let ::nulls::Null::Value(inner) = &#receiver.#ident {
+                    <#ty as ::nulls::ApplyPatch>::validate(inner)?;
+                }
+            });
+        }
+    }
+
+    checks
+}
+
+/// Implements `nulls::path::Reflect` for the patch, if it derives `Deserialize`.
+#[cfg(feature = "serde")]
+fn reflect(input: &DeriveInput, container: &Container, fields: &[PatchField]) -> TokenStream {
+    if !container.derives("Deserialize") {
+        return TokenStream::new();
+    }
+
+    let patch = format_ident!("{}Patch", input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote!(where));
+
+    if !input.generics.params.is_empty() {
+        for field in fields {
+            let ty = field.patch_ty();
+
+            where_clause.predicates.push(if field.nested.is_some() {
+                syn::parse_quote!(#ty: ::nulls::path::Reflect)
+            } else {
+                syn::parse_quote!(#ty: ::nulls::serde::__serde::de::DeserializeOwned)
+            });
+        }
+    }
+
+    let infos = fields.iter().map(|field| {
+        let name = &field.name;
+        let nullable = field.nullable || field.on_null != OnNull::Error;
+        let nested = field.nested.is_some();
+
+        quote!(::nulls::path::FieldInfo { name: #name, nullable: #nullable, nested: #nested })
+    });
+
+    let leaves = fields.iter().map(|field| {
+        let ident = field.ident;
+        let name = &field.name;
+
+        let check = (!field.nullable && field.on_null == OnNull::Error).then(|| {
+            quote! {
+                if value.is_null() {
+                    return ::core::result::Result::Err(::nulls::path::PathError::NotNullable(#name.into()));
+                }
+            }
+        });
+
+        let aliases = &field.aliases;
+
+        quote! {
+            [field] if field == #name #(|| field == #aliases)* => {
+                #check
+                self.#ident = ::nulls::path::__from_value(value)?;
+                ::core::result::Result::Ok(())
+            }
+        }
+    });
+
+    let branches = fields.iter().filter(|field| field.nested.is_some()).map(|field| {
+        let ident = field.ident;
+        let name = &field.name;
+        let aliases = &field.aliases;
+
+        quote! {
+            [field, rest @ ..] if field == #name #(|| field == #aliases)* => {
+                if !self.#ident.is_value() {
+                    self.#ident = ::nulls::Null::Value(::core::default::Default::default());
+                }
+
+                match &mut self.#ident {
+                    ::nulls::Null::Value(inner) => {
+                        ::nulls::path::Reflect::set_path(inner, rest, value).map_err(|error| error.__prefixed(#name))
+                    }
+                    _ => ::core::unreachable!(),
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::nulls::path::Reflect for #patch #ty_generics #where_clause {
+            const FIELDS: &'static [::nulls::path::FieldInfo] = &[#(#infos),*];
+
+            fn set_path(
+                &mut self,
+                path: &[::std::string::String],
+                value: ::nulls::Null<::nulls::serde::__serde_json::Value>,
+            ) -> ::core::result::Result<(), ::nulls::path::PathError> {
+                match path {
+                    #(#leaves)*
+                    #(#branches)*
+                    _ => ::core::result::Result::Err(::nulls::path::PathError::UnknownField(path.join("."))),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn schema(input: &DeriveInput, container: &Container, fields: &[PatchField]) -> syn::Result<TokenStream> {
+    Ok(if container.schema { crate::schema::expand(input, fields) } else { TokenStream::new() })
+}
+
+#[cfg(not(feature = "serde"))]
+fn schema(input: &DeriveInput, container: &Container, _: &[PatchField]) -> syn::Result<TokenStream> {
+    if container.schema {
+        return Err(syn::Error::new_spanned(&input.ident, "`schema` needs the `serde` feature of nulls"));
+    }
+
+    Ok(TokenStream::new())
+}
+
+#[cfg(feature = "avro")]
+fn avro(input: &DeriveInput, container: &Container, fields: &[PatchField]) -> syn::Result<TokenStream> {
+    if container.avro { crate::avro::expand(input, fields) } else { Ok(TokenStream::new()) }
+}
+
+#[cfg(not(feature = "avro"))]
+fn avro(input: &DeriveInput, container: &Container, _: &[PatchField]) -> syn::Result<TokenStream> {
+    if container.avro {
+        return Err(syn::Error::new_spanned(&input.ident, "`avro` needs the `apache-avro` feature of nulls"));
+    }
+
+    Ok(TokenStream::new())
+}
+
+/// The extra field collecting unknown members, for `#[null(overflow = "...")]`.
+#[cfg(feature = "serde")]
+fn overflow(input: &DeriveInput, container: &Container) -> syn::Result<TokenStream> {
+    let vis = &input.vis;
+
+    if container.overflow.is_some() && !container.derives("Serialize") && !container.derives("Deserialize") {
+        return Err(syn::Error::new_spanned(&input.ident, "`overflow` needs the patch to derive `Serialize` or `Deserialize`"));
+    }
+
+    Ok(container.overflow.as_ref().map_or_else(TokenStream::new, |ident| {
+        quote! {
+            /// Members that match no other field, such as ones written by an older version.
+            #[serde(flatten, with = "::nulls::serde::overflow")]
+            #vis #ident: ::nulls::Null<
+                ::nulls::serde::__serde_json::Map<::std::string::String, ::nulls::serde::__serde_json::Value>,
+            >,
+        }
+    }))
+}
+
+#[cfg(not(feature = "serde"))]
+fn overflow(input: &DeriveInput, container: &Container) -> syn::Result<TokenStream> {
+    if container.overflow.is_some() {
+        return Err(syn::Error::new_spanned(&input.ident, "`overflow` needs the `serde` feature of nulls"));
+    }
+
+    Ok(TokenStream::new())
+}
+
+#[cfg(not(feature = "serde"))]
+fn reflect(_: &DeriveInput, _: &Container, _: &[PatchField]) -> TokenStream {
+    TokenStream::new()
+}
+
+/// Whether the patch derives either serde trait, and so takes `#[serde(...)]` attributes.
+#[cfg(feature = "serde")]
+fn derives_serde(container: &Container) -> bool {
+    container.derives("Serialize") || container.derives("Deserialize")
+}
+
+#[cfg(feature = "serde")]
+fn serde_container_attributes(container: &Container) -> TokenStream {
+    if !derives_serde(container) {
+        return TokenStream::new();
+    }
+
+    let serialize = container.derives("Serialize").then(|| quote!(::nulls::serde::__serde::Serialize));
+    let deserialize = container.derives("Deserialize").then(|| quote!(::nulls::serde::__serde::Deserialize));
+    let derives = serialize.into_iter().chain(deserialize);
+
+    quote! {
+        #[derive(#(#derives),*)]
+        #[serde(crate = "::nulls::serde::__serde")]
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn serde_container_attributes(_: &Container) -> TokenStream {
+    TokenStream::new()
+}
+
+#[cfg(feature = "serde")]
+fn serde_field_attributes(container: &Container, field: &PatchField) -> TokenStream {
+    if !derives_serde(container) {
+        return TokenStream::new();
+    }
+
+    let name = &field.name;
+    let aliases = &field.aliases;
+    quote!(#[serde(default, rename = #name, #(alias = #aliases,)* skip_serializing_if = "::nulls::serde::is_undefined")])
+}
+
+#[cfg(not(feature = "serde"))]
+fn serde_field_attributes(_: &Container, _: &PatchField) -> TokenStream {
+    TokenStream::new()
+}
@@ -0,0 +1,42 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attr::patch_fields;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = patch_fields(&input, "Record")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let idents: Vec<_> = fields.iter().map(|field| field.ident).collect();
+    let names: Vec<_> = fields.iter().map(|field| &field.name).collect();
+    let olds: Vec<_> = idents.iter().map(|ident| format_ident!("__old_{}", ident)).collect();
+
+    Ok(quote! {
+        impl #impl_generics ::nulls::changeset::Record for #name #ty_generics #where_clause {
+            fn apply_recorded(
+                &mut self,
+                patch: <Self as ::nulls::ApplyPatch>::Patch,
+            ) -> ::core::result::Result<::nulls::changeset::ChangeSet, ::nulls::changeset::ChangeSetError> {
+                #(
+                    let #olds = if patch.#idents.is_undefined() {
+                        ::core::option::Option::None
+                    } else {
+                        ::core::option::Option::Some(::nulls::serde::__serde_json::to_value(&self.#idents)?)
+                    };
+                )*
+
+                ::nulls::ApplyPatch::apply(self, patch)?;
+
+                let mut changes = ::nulls::changeset::ChangeSet::new();
+                #(
+                    if let ::core::option::Option::Some(old) = #olds {
+                        changes.push(#names, old, ::nulls::serde::__serde_json::to_value(&self.#idents)?);
+                    }
+                )*
+                ::core::result::Result::Ok(changes)
+            }
+        }
+    })
+}
@@ -0,0 +1,150 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Expr, ExprLit, Lit, Meta, Type};
+
+use crate::attr::{OnNull, PatchField};
+use crate::util::option_inner;
+
+/// Generates `{Name}Patch::schema()` returning an OpenAPI 3.1 / JSON Schema object.
+pub fn expand(input: &DeriveInput, fields: &[PatchField]) -> TokenStream {
+    let vis = &input.vis;
+    let patch = format_ident!("{}Patch", input.ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let title = patch.to_string();
+
+    let properties = fields.iter().map(|field| {
+        let name = &field.name;
+        let nullable = field.nullable || field.on_null != OnNull::Error;
+
+        let mut schema = match &field.nested {
+            Some(ty) => quote!(<#ty>::schema()),
+            None => type_schema(field.patch_ty()),
+        };
+
+        if nullable {
+            schema = quote!(::nulls::serde::__serde_json::json!({ "anyOf": [#schema, { "type": "null" }] }));
+        }
+
+        let description = docs(field).map(|docs| {
+            quote! {
+                if let ::nulls::serde::__serde_json::Value::Object(schema) = &mut schema {
+                    schema.insert("description".into(), #docs.into());
+                }
+            }
+        });
+
+        quote! {
+            let mut schema = #schema;
+            #description
+            properties.insert(#name.into(), schema);
+        }
+    });
+
+    let required = fields.iter().filter(|field| field.required).map(|field| &field.name);
+
+    quote! {
+        impl #impl_generics #patch #ty_generics #where_clause {
+            /// JSON Schema for the patch, following OpenAPI 3.1: only `required` fields are
+            /// listed as required, and fields that accept `Null` allow `null`.
+            #vis fn schema() -> ::nulls::serde::__serde_json::Value {
+                let mut properties = ::nulls::serde::__serde_json::Map::new();
+                #(#properties)*
+
+                ::nulls::serde::__serde_json::json!({
+                    "title": #title,
+                    "type": "object",
+                    "properties": properties,
+                    "required": [#(#required),*],
+                })
+            }
+        }
+    }
+}
+
+/// Schema for a field type, recognized by the last segment of its path. Types that are not
+/// recognized accept anything.
+fn type_schema(ty: &Type) -> TokenStream {
+    let json = quote!(::nulls::serde::__serde_json::json!);
+
+    if let Some(inner) = option_inner(ty) {
+        let inner = type_schema(inner);
+        return quote!(#json({ "anyOf": [#inner, { "type": "null" }] }));
+    }
+
+    match ty {
+        Type::Reference(reference) => return type_schema(&reference.elem),
+        Type::Array(array) => {
+            let items = type_schema(&array.elem);
+            return quote!(#json({ "type": "array", "items": #items }));
+        }
+        Type::Slice(slice) => {
+            let items = type_schema(&slice.elem);
+            return quote!(#json({ "type": "array", "items": #items }));
+        }
+        _ => {}
+    }
+
+    let Type::Path(path) = ty else {
+        return quote!(#json({}));
+    };
+
+    let Some(segment) = path.path.segments.last() else {
+        return quote!(#json({}));
+    };
+
+    let arguments: Vec<&Type> = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    match (segment.ident.to_string().as_str(), arguments.as_slice()) {
+        ("String" | "str" | "char", _) => quote!(#json({ "type": "string" })),
+        ("bool", _) => quote!(#json({ "type": "boolean" })),
+        ("i8" | "i16" | "i32" | "u8" | "u16", _) => quote!(#json({ "type": "integer", "format": "int32" })),
+        ("i64" | "u32" | "u64" | "i128" | "u128" | "isize" | "usize", _) => {
+            quote!(#json({ "type": "integer", "format": "int64" }))
+        }
+        ("f32", _) => quote!(#json({ "type": "number", "format": "float" })),
+        ("f64", _) => quote!(#json({ "type": "number", "format": "double" })),
+        ("Null", [inner]) => {
+            let inner = type_schema(inner);
+            quote!(#json({ "anyOf": [#inner, { "type": "null" }] }))
+        }
+        ("Box" | "Rc" | "Arc", [inner]) => type_schema(inner),
+        ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [items]) => {
+            let items = type_schema(items);
+            quote!(#json({ "type": "array", "items": #items }))
+        }
+        ("HashMap" | "BTreeMap", [_, values, ..]) => {
+            let values = type_schema(values);
+            quote!(#json({ "type": "object", "additionalProperties": #values }))
+        }
+        _ => quote!(#json({})),
+    }
+}
+
+/// The field's doc comment, with the leading space of each line removed.
+pub fn docs(field: &PatchField) -> Option<String> {
+    let lines: Vec<String> = field
+        .field
+        .attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(doc), .. }) => Some(doc.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_owned())
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n").trim().to_owned())
+}
@@ -0,0 +1,54 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{parse_quote, Attribute, Data, DeriveInput, Field, Fields, Meta, PathArguments, Type};
+
+pub fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    for field in named_fields_mut(&mut input)? {
+        if !is_null_type(&field.ty) {
+            continue;
+        }
+
+        if !has_serde_option(&field.attrs, "default") {
+            field.attrs.push(parse_quote!(#[serde(default)]));
+        }
+
+        if !has_serde_option(&field.attrs, "skip_serializing_if") {
+            field.attrs.push(parse_quote!(#[serde(skip_serializing_if = "::nulls::serde::is_undefined")]));
+        }
+    }
+
+    Ok(input.into_token_stream())
+}
+
+/// Whether one of the `#[serde(...)]` attributes already sets `option`, which serde would
+/// reject as a duplicate.
+fn has_serde_option(attrs: &[Attribute], option: &str) -> bool {
+    attrs.iter().filter(|attr| attr.path().is_ident("serde")).any(|attr| match &attr.meta {
+        Meta::List(list) => list.tokens.clone().into_iter().any(|token| matches!(token, TokenTree::Ident(ident) if ident == option)),
+        _ => false,
+    })
+}
+
+/// Like `util::named_fields`, but for rewriting the fields in place.
+fn named_fields_mut(input: &mut DeriveInput) -> syn::Result<Vec<&mut Field>> {
+    let span = input.ident.clone();
+
+    match &mut input.data {
+        Data::Struct(data) => match &mut data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter_mut().collect()),
+            _ => Err(syn::Error::new_spanned(span, "`undefined_skip` can only be used on structs with named fields")),
+        },
+        _ => Err(syn::Error::new_spanned(span, "`undefined_skip` can only be used on structs")),
+    }
+}
+
+/// Whether a field is written as `Null<T>`, with or without a path. Type aliases of `Null`
+/// are not recognized.
+fn is_null_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Null" && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+        }),
+        _ => false,
+    }
+}
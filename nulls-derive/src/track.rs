@@ -0,0 +1,123 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attr::patch_fields;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = patch_fields(&input, "TrackChanges")?;
+    let vis = &input.vis;
+    let name = &input.ident;
+    let patch = format_ident!("{}Patch", name);
+    let tracker = format_ident!("{}Tracker", name);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+
+    let mut methods = Vec::new();
+
+    for field in &fields {
+        if field.merges() {
+            return Err(syn::Error::new_spanned(
+                field.field,
+                "TrackChanges does not support `nested` or `map` fields; mark them `skip` to leave them untracked",
+            ));
+        }
+
+        let vis = &field.field.vis;
+        let ident = field.ident;
+        let ty = field.ty;
+        let set = format_ident!("set_{}", ident);
+        let set_doc = format!("Sets `{}` and records it as `Value`.", ident);
+
+        let assign = if field.optional {
+            quote!(::core::option::Option::Some(value))
+        } else {
+            quote!(value)
+        };
+
+        methods.push(quote! {
+            #[doc = #set_doc]
+            #vis fn #set(&mut self, value: impl ::core::convert::Into<#ty>) -> &mut Self {
+                let value: #ty = ::core::convert::Into::into(value);
+                self.changes.#ident = ::nulls::Null::Value(::core::clone::Clone::clone(&value));
+                self.value.#ident = #assign;
+                self
+            }
+        });
+
+        if field.nullable {
+            let clear = format_ident!("clear_{}", ident);
+            let clear_doc = format!("Sets `{}` to `None` and records it as `Null`.", ident);
+
+            methods.push(quote! {
+                #[doc = #clear_doc]
+                #vis fn #clear(&mut self) -> &mut Self {
+                    self.changes.#ident = ::nulls::Null::Null;
+                    self.value.#ident = ::core::option::Option::None;
+                    self
+                }
+            });
+        }
+    }
+
+    let doc = format!(
+        "Wraps a [`{}`] and records every change made through its setters as a [`{}`], generated by `#[derive(TrackChanges)]`.",
+        name, patch
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #tracker #generics #where_clause {
+            value: #name #ty_generics,
+            changes: #patch #ty_generics,
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Starts tracking changes to this value.
+            #vis fn track(self) -> #tracker #ty_generics {
+                #tracker {
+                    value: self,
+                    changes: ::core::default::Default::default(),
+                }
+            }
+        }
+
+        impl #impl_generics #tracker #ty_generics #where_clause {
+            #(#methods)*
+
+            /// The changes recorded so far.
+            #vis fn changes(&self) -> &#patch #ty_generics {
+                &self.changes
+            }
+
+            /// Returns the changes recorded so far and starts over with none.
+            #vis fn take_changes(&mut self) -> #patch #ty_generics {
+                ::core::mem::take(&mut self.changes)
+            }
+
+            /// Returns `true` if a setter has been called since tracking started or the
+            /// changes were last taken.
+            #vis fn is_dirty(&self) -> bool {
+                !self.changes.is_empty()
+            }
+
+            #vis fn into_inner(self) -> #name #ty_generics {
+                self.value
+            }
+
+            /// Returns the value and the changes recorded since tracking started or the
+            /// changes were last taken.
+            #vis fn into_parts(self) -> (#name #ty_generics, #patch #ty_generics) {
+                (self.value, self.changes)
+            }
+        }
+
+        impl #impl_generics ::core::ops::Deref for #tracker #ty_generics #where_clause {
+            type Target = #name #ty_generics;
+
+            fn deref(&self) -> &Self::Target {
+                &self.value
+            }
+        }
+    })
+}
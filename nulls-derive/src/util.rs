@@ -0,0 +1,75 @@
+use quote::format_ident;
+use syn::{parse_quote, Data, DeriveInput, Field, Fields, GenericArgument, PathArguments, Type};
+
+/// Returns the named fields of a struct, or an error pointing at the item otherwise.
+pub fn named_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<Vec<&'a Field>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            _ => Err(syn::Error::new_spanned(input, format!("{} can only be derived for structs with named fields", derive))),
+        },
+        _ => Err(syn::Error::new_spanned(input, format!("{} can only be derived for structs", derive))),
+    }
+}
+
+/// Returns `T` for a field written as `Option<T>`. Type aliases of `Option` are not
+/// recognized.
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => match arguments.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the type `#[derive(Patch)]` generates for `ty`: `a::Address<T>` becomes
+/// `a::AddressPatch<T>`.
+pub fn patch_type(ty: &Type) -> syn::Result<Type> {
+    let mut patch = ty.clone();
+
+    match &mut patch {
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last_mut().expect("path has a segment");
+            segment.ident = format_ident!("{}Patch", segment.ident);
+            Ok(patch)
+        }
+        _ => Err(syn::Error::new_spanned(ty, "`nested` fields must be a struct type such as `Address`")),
+    }
+}
+
+/// Returns `ty`, a map such as `HashMap<K, V>`, with its value type wrapped in `Null`.
+pub fn map_patch_type(ty: &Type) -> syn::Result<Type> {
+    let mut patch = ty.clone();
+
+    if let Type::Path(path) = &mut patch {
+        if let Some(PathArguments::AngleBracketed(arguments)) = path.path.segments.last_mut().map(|s| &mut s.arguments) {
+            let mut value = arguments.args.iter_mut().filter_map(|argument| match argument {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            });
+
+            if let Some(value) = value.nth(1) {
+                *value = parse_quote!(::nulls::Null<#value>);
+                return Ok(patch);
+            }
+        }
+    }
+
+    Err(syn::Error::new_spanned(ty, "`map` fields must be a map type such as `HashMap<K, V>`"))
+}
@@ -0,0 +1,171 @@
+//! Apache Avro support through `apache-avro`.
+//!
+//! `Null<T>` maps to the `["null", T]` union, the same schema Avro uses for `Option<T>`, so
+//! patch structs can derive `AvroSchema` and be registered like any other record. `Value` is
+//! written as the `T` branch, and `Null` as the `null` branch. Avro records have no absent
+//! fields, so `Undefined` is written as the schema default, `null`.
+//!
+//! ```
+//! use apache_avro::{from_avro_datum, from_value, to_avro_datum, to_value, AvroSchema};
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(AvroSchema, Serialize, Deserialize, PartialEq, Debug)]
+//! struct UserPatch {
+//!     #[avro(default = "null")]
+//!     name: Null<String>,
+//!     #[avro(default = "null")]
+//!     age: Null<i32>,
+//! }
+//!
+//! let schema = UserPatch::get_schema();
+//! let patch = UserPatch { name: nulls::new("nulls".into()), age: Null::Null };
+//!
+//! let bytes = to_avro_datum(&schema, to_value(&patch).unwrap()).unwrap();
+//! let value = from_avro_datum(&schema, &mut bytes.as_slice(), None).unwrap();
+//! assert_eq!(from_value::<UserPatch>(&value).unwrap(), patch);
+//!
+//! let patch = UserPatch { name: Null::Undefined, age: nulls::new(21) };
+//! let bytes = to_avro_datum(&schema, to_value(&patch).unwrap()).unwrap();
+//! let value = from_avro_datum(&schema, &mut bytes.as_slice(), None).unwrap();
+//! assert_eq!(from_value::<UserPatch>(&value).unwrap().name, Null::Null);
+//! ```
+//!
+//! With the `derive` feature, `#[null(avro)]` implements `AvroSchema` for the
+//! `{Name}Patch` generated by `#[derive(Patch)]`, and [`to_value`] fills the `Undefined`
+//! fields the patch leaves out of its serialized form with that default.
+//!
+//! The default also covers schema evolution: a field added to the patch reads as `Null`
+//! from events written before it existed.
+//!
+//! ```
+//! use apache_avro::{from_avro_datum, from_value, to_avro_datum, to_value, AvroSchema};
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//!
+//! mod v1 {
+//!     use apache_avro::AvroSchema;
+//!     use nulls::Null;
+//!     use serde::Serialize;
+//!
+//!     #[derive(AvroSchema, Serialize)]
+//!     pub struct UserPatch {
+//!         #[avro(default = "null")]
+//!         pub name: Null<String>,
+//!     }
+//! }
+//!
+//! #[derive(AvroSchema, Deserialize)]
+//! struct UserPatch {
+//!     #[avro(default = "null")]
+//!     name: Null<String>,
+//!     #[avro(default = "null")]
+//!     age: Null<i32>,
+//! }
+//!
+//! let writer = v1::UserPatch::get_schema();
+//! let old = v1::UserPatch { name: nulls::new("nulls".into()) };
+//! let bytes = to_avro_datum(&writer, to_value(&old).unwrap()).unwrap();
+//!
+//! let value = from_avro_datum(&writer, &mut bytes.as_slice(), Some(&UserPatch::get_schema())).unwrap();
+//! let patch = from_value::<UserPatch>(&value).unwrap();
+//! assert_eq!((patch.name, patch.age), (nulls::new("nulls".into()), Null::Null));
+//! ```
+
+use ::serde::Serialize;
+use apache_avro::schema::derive::AvroSchemaComponent;
+use apache_avro::schema::{Name, Namespace, RecordField, RecordFieldOrder, RecordSchema, Schema};
+use apache_avro::types::Value;
+use apache_avro::AvroSchema;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::Null;
+
+#[doc(hidden)]
+pub use ::apache_avro as __apache_avro;
+
+impl<T> AvroSchemaComponent for Null<T>
+where T: AvroSchemaComponent,
+{
+    fn get_schema_in_ctxt(named_schemas: &mut HashMap<Name, Schema>, enclosing_namespace: &Namespace) -> Schema {
+        Option::<T>::get_schema_in_ctxt(named_schemas, enclosing_namespace)
+    }
+}
+
+/// Converts `value` into an Avro value resolved against its own schema, so fields left out
+/// of the serialized form, such as `Undefined` ones skipped by `skip_serializing_if`, take
+/// the schema default. The error is boxed, as `apache_avro::Error` is large.
+///
+/// ```
+/// use apache_avro::{from_value, to_avro_datum, AvroSchema};
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(AvroSchema, Serialize, Deserialize, PartialEq, Debug)]
+/// struct UserPatch {
+///     #[avro(default = "null")]
+///     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+///     name: Null<String>,
+/// }
+///
+/// let value = nulls::avro::to_value(&UserPatch { name: Null::Undefined }).unwrap();
+/// assert!(to_avro_datum(&UserPatch::get_schema(), value.clone()).is_ok());
+/// assert_eq!(from_value::<UserPatch>(&value).unwrap().name, Null::Null);
+/// ```
+pub fn to_value<T: Serialize + AvroSchema>(value: &T) -> Result<Value, Box<apache_avro::Error>> {
+    Ok(apache_avro::to_value(value)?.resolve(&T::get_schema())?)
+}
+
+/// A nullable record field for `#[null(avro)]`, defaulting to `null`.
+#[doc(hidden)]
+pub fn __field(name: &str, aliases: &[&str], doc: Option<&str>, schema: Schema) -> RecordField {
+    RecordField {
+        name: name.to_owned(),
+        doc: doc.map(str::to_owned),
+        aliases: (!aliases.is_empty()).then(|| aliases.iter().map(|&alias| alias.to_owned()).collect()),
+        default: Some(serde_json::Value::Null),
+        schema,
+        order: RecordFieldOrder::Ascending,
+        position: 0,
+        custom_attributes: BTreeMap::new(),
+    }
+}
+
+/// The record schema for `#[null(avro)]`, or a reference to it once it has been defined.
+/// `name` must already be a valid Avro name.
+#[doc(hidden)]
+pub fn __record<F>(
+    name: &str,
+    named_schemas: &mut HashMap<Name, Schema>,
+    enclosing_namespace: &Namespace,
+    fields: F,
+) -> Schema
+where
+    F: FnOnce(&mut HashMap<Name, Schema>, &Namespace) -> Vec<RecordField>,
+{
+    // `#[null(avro)]` checks the name at compile time, so it is not parsed again here.
+    let name = Name { name: name.to_owned(), namespace: None }.fully_qualified_name(enclosing_namespace);
+
+    if named_schemas.contains_key(&name) {
+        return Schema::Ref { name };
+    }
+
+    named_schemas.insert(name.clone(), Schema::Ref { name: name.clone() });
+
+    let mut fields = fields(named_schemas, &name.namespace);
+    for (position, field) in fields.iter_mut().enumerate() {
+        field.position = position;
+    }
+
+    let schema = Schema::Record(RecordSchema {
+        name: name.clone(),
+        aliases: None,
+        doc: None,
+        lookup: fields.iter().map(|field| (field.name.clone(), field.position)).collect(),
+        fields,
+        attributes: BTreeMap::new(),
+    });
+
+    named_schemas.insert(name, schema.clone());
+    schema
+}
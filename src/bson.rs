@@ -0,0 +1,87 @@
+//! MongoDB helpers for `Null` fields.
+
+use ::bson::{Bson, Document};
+use ::serde::Serialize;
+
+use crate::serde::ErrorOnUndefined;
+use crate::Null;
+
+/// `Null` and `Undefined` both become `Bson::Null`; leave `Undefined` fields out of
+/// update documents with [`update_document`].
+impl<T: Into<Bson>> From<Null<T>> for Bson {
+    fn from(value: Null<T>) -> Bson {
+        match value {
+            Null::Value(value) => value.into(),
+            Null::Undefined | Null::Null => Bson::Null,
+        }
+    }
+}
+
+/// How [`update_document`] writes fields that are `Null`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum NullUpdate {
+    /// `$set` the field to `null`.
+    #[default]
+    Set,
+    /// `$unset` the field.
+    Unset,
+}
+
+/// Builds an update document from a patch struct: `Value` fields go into `$set`, `Null`
+/// fields are handled according to `nulls`, and `Undefined` fields are left out.
+///
+/// `Undefined` fields must be skipped with `skip_serializing_if = "nulls::serde::is_undefined"`,
+/// as `#[derive(Patch)]` does. One that is not would read like `Null`, so it is rejected
+/// with an error instead of clearing the field.
+///
+/// ```
+/// use bson::doc;
+/// use nulls::bson::{update_document, NullUpdate};
+/// use nulls::Null;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Patch {
+///     #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+///     name: Null<String>,
+///     #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+///     bio: Null<String>,
+///     #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+///     email: Null<String>,
+/// }
+///
+/// let patch = Patch { name: nulls::new("nulls".into()), bio: nulls::null(), email: nulls::undefined() };
+///
+/// let update = update_document(&patch, NullUpdate::Set).unwrap();
+/// assert_eq!(update, doc! { "$set": { "name": "nulls", "bio": null } });
+///
+/// let update = update_document(&patch, NullUpdate::Unset).unwrap();
+/// assert_eq!(update, doc! { "$set": { "name": "nulls" }, "$unset": { "bio": "" } });
+/// ```
+pub fn update_document<T: Serialize>(patch: &T, nulls: NullUpdate) -> Result<Document, ::bson::ser::Error> {
+    let mut set = Document::new();
+    let mut unset = Document::new();
+
+    for (key, value) in ::bson::to_document(&ErrorOnUndefined(patch))? {
+        match (value, nulls) {
+            (Bson::Null, NullUpdate::Unset) => {
+                unset.insert(key, "");
+            }
+            (value, _) => {
+                set.insert(key, value);
+            }
+        }
+    }
+
+    let mut update = Document::new();
+
+    if !set.is_empty() {
+        update.insert("$set", set);
+    }
+
+    if !unset.is_empty() {
+        update.insert("$unset", unset);
+    }
+
+    Ok(update)
+}
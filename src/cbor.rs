@@ -0,0 +1,113 @@
+//! CBOR support through `ciborium`.
+//!
+//! CBOR is a binary format, so `Null` fields use the lossless [`compact`](crate::serde::compact)
+//! encoding by default and round-trip all three states:
+//!
+//! ```
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Patch {
+//!     name: Null<String>,
+//!     bio: Null<String>,
+//!     age: Null<u32>,
+//! }
+//!
+//! let patch = Patch { name: nulls::new("nulls".into()), bio: Null::Null, age: Null::Undefined };
+//!
+//! let mut bytes = Vec::new();
+//! ciborium::into_writer(&patch, &mut bytes).unwrap();
+//! assert_eq!(ciborium::from_reader::<Patch, _>(bytes.as_slice()).unwrap(), patch);
+//! ```
+//!
+//! Peers that expect plain CBOR values should use
+//! [`double_option`](crate::serde::double_option) with `default` and `skip_serializing_if`
+//! instead, so `Undefined` is an absent key, `Null` is CBOR `null` and `Value` is the value
+//! itself. Through serde, CBOR `undefined` (simple value 23) reads the same as `null`,
+//! because serde has no way to tell them apart:
+//!
+//! ```
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Patch {
+//!     #[serde(default, with = "nulls::serde::double_option", skip_serializing_if = "nulls::serde::is_undefined")]
+//!     name: Null<String>,
+//!     #[serde(default, with = "nulls::serde::double_option", skip_serializing_if = "nulls::serde::is_undefined")]
+//!     bio: Null<String>,
+//! }
+//!
+//! let patch = Patch { name: Null::Undefined, bio: Null::Null };
+//!
+//! let mut bytes = Vec::new();
+//! ciborium::into_writer(&patch, &mut bytes).unwrap();
+//! assert_eq!(bytes, [0xa1, 0x63, b'b', b'i', b'o', 0xf6]);
+//! assert_eq!(ciborium::from_reader::<Patch, _>(bytes.as_slice()).unwrap(), patch);
+//!
+//! let bytes = [0xa1, 0x63, b'b', b'i', b'o', 0xf7];
+//! assert_eq!(ciborium::from_reader::<Patch, _>(bytes.as_slice()).unwrap(), patch);
+//! ```
+//!
+//! A standalone `Null`, such as a whole message, can use CBOR's native `undefined` with
+//! [`to_vec`] and [`from_slice`]. That encoding has no room for a `Value` that is itself
+//! written as `null` or `undefined`, such as `None` or `()`, so [`to_vec`] rejects those;
+//! use the compact encoding for `Null<Option<T>>`.
+
+use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+
+use crate::Null;
+
+/// The CBOR encoding of `null`, simple value 22.
+const NULL: u8 = 0xf6;
+/// The CBOR encoding of `undefined`, simple value 23.
+const UNDEFINED: u8 = 0xf7;
+
+/// Encodes a standalone `Null`: `Undefined` as CBOR `undefined`, `Null` as CBOR `null` and
+/// `Value` as the value itself. A `Value` that encodes as `null` or `undefined` would read
+/// back as another state, so it is an error.
+///
+/// ```
+/// use nulls::Null;
+///
+/// assert_eq!(nulls::cbor::to_vec(&Null::<u8>::Undefined).unwrap(), [0xf7]);
+/// assert_eq!(nulls::cbor::to_vec(&Null::<u8>::Null).unwrap(), [0xf6]);
+/// assert_eq!(nulls::cbor::to_vec(&nulls::new(1u8)).unwrap(), [0x01]);
+/// assert!(nulls::cbor::to_vec(&nulls::new(None::<u8>)).is_err());
+/// ```
+pub fn to_vec<T: Serialize>(value: &Null<T>) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+    match value {
+        Null::Undefined => Ok(vec![UNDEFINED]),
+        Null::Null => Ok(vec![NULL]),
+        Null::Value(value) => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(value, &mut bytes)?;
+
+            if let [NULL | UNDEFINED] = bytes.as_slice() {
+                return Err(ciborium::ser::Error::Value("a Value cannot be encoded as CBOR null or undefined".into()));
+            }
+
+            Ok(bytes)
+        }
+    }
+}
+
+/// Decodes a standalone `Null` written by [`to_vec`] or any other CBOR encoder: `undefined`
+/// is `Undefined`, `null` is `Null` and anything else is decoded into `T`.
+///
+/// ```
+/// use nulls::Null;
+///
+/// assert_eq!(nulls::cbor::from_slice::<u8>(&[0xf7]).unwrap(), Null::Undefined);
+/// assert_eq!(nulls::cbor::from_slice::<u8>(&[0xf6]).unwrap(), Null::Null);
+/// assert_eq!(nulls::cbor::from_slice::<u8>(&[0x01]).unwrap(), nulls::new(1));
+/// ```
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<Null<T>, ciborium::de::Error<std::io::Error>> {
+    match bytes {
+        [UNDEFINED] => Ok(Null::Undefined),
+        [NULL] => Ok(Null::Null),
+        bytes => ciborium::from_reader(bytes).map(Null::Value),
+    }
+}
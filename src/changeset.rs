@@ -0,0 +1,129 @@
+//! Applying patches while recording what changed, for audit logs.
+//!
+//! [`Record::apply_recorded`] applies a patch like [`ApplyPatch::apply`] and returns a
+//! [`ChangeSet`] with the old and new value of every field that actually changed, as JSON.
+//! The change set serializes as a plain list, ready to be stored in an audit table.
+//!
+//! With the `derive` feature, `#[derive(Record)]` implements [`Record`] for any type that
+//! also derives `Patch` and `ApplyPatch`.
+
+use ::serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Display;
+
+use crate::patch::{ApplyPatch, PatchError};
+
+#[cfg(feature = "derive")]
+pub use nulls_derive::Record;
+
+/// A field whose value changed, with both values as JSON.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Change {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// The changes made by one patch, in field declaration order.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChangeSet {
+    changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    pub fn new() -> Self {
+        ChangeSet::default()
+    }
+
+    /// Records a change to `field`, unless `old` and `new` are equal.
+    pub fn push(&mut self, field: impl Into<String>, old: Value, new: Value) {
+        if old != new {
+            self.changes.push(Change { field: field.into(), old, new });
+        }
+    }
+
+    pub fn get(&self, field: &str) -> Option<&Change> {
+        self.changes.iter().find(|change| change.field == field)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Change> {
+        self.changes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl IntoIterator for ChangeSet {
+    type Item = Change;
+    type IntoIter = std::vec::IntoIter<Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ChangeSet {
+    type Item = &'a Change;
+    type IntoIter = std::slice::Iter<'a, Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+#[derive(Debug)]
+pub enum ChangeSetError {
+    /// The patch was rejected; nothing was changed.
+    Patch(PatchError),
+    /// A field value could not be serialized to JSON.
+    Json(serde_json::Error),
+}
+
+impl Display for ChangeSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeSetError::Patch(error) => write!(f, "{}", error),
+            ChangeSetError::Json(error) => write!(f, "failed to serialize a field value: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ChangeSetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChangeSetError::Patch(error) => Some(error),
+            ChangeSetError::Json(error) => Some(error),
+        }
+    }
+}
+
+impl From<PatchError> for ChangeSetError {
+    fn from(error: PatchError) -> Self {
+        ChangeSetError::Patch(error)
+    }
+}
+
+impl From<serde_json::Error> for ChangeSetError {
+    fn from(error: serde_json::Error) -> Self {
+        ChangeSetError::Json(error)
+    }
+}
+
+/// A type that can apply a patch while recording the changes, usually implemented with
+/// `#[derive(Record)]` next to `#[derive(ApplyPatch)]`. Every field must implement
+/// `Serialize`.
+pub trait Record: ApplyPatch {
+    /// Applies `patch` and returns the fields whose value changed.
+    ///
+    /// Old values are serialized before anything is written, so a `Patch` error or a
+    /// failure to serialize them leaves `self` unchanged. New values are serialized after
+    /// the patch is applied; if that fails, `self` is already patched.
+    fn apply_recorded(&mut self, patch: Self::Patch) -> Result<ChangeSet, ChangeSetError>;
+}
@@ -0,0 +1,52 @@
+//! ClickHouse helpers for `Null` fields.
+//!
+//! The `RowBinary` format used by the `clickhouse` crate is not human-readable, so a bare
+//! `Null<T>` field would be written with the [`compact`](crate::serde::compact) encoding.
+//! Mark fields that map to `Nullable(T)` columns with [`nullable`] instead:
+//!
+//! ```
+//! use clickhouse::Row;
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Row, Serialize, Deserialize)]
+//! struct ChangeEvent {
+//!     id: u64,
+//!     #[serde(with = "nulls::clickhouse::nullable")]
+//!     email: Null<String>,
+//! }
+//! ```
+
+/// Writes `Nullable(T)` values: `Null` as NULL and `Value` as the value, with
+/// `Undefined` following [`undefined_policy`](crate::serde::undefined_policy), which can
+/// only write it as NULL or fail. NULL
+/// reads back as `Null`.
+pub mod nullable {
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::serde::check_undefined;
+    use crate::Null;
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Null::Value(value) => serializer.serialize_some(value),
+            Null::Null => serializer.serialize_none(),
+            Null::Undefined => {
+                check_undefined()?;
+                serializer.serialize_none()
+            }
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Null::from_option)
+    }
+}
@@ -0,0 +1,1134 @@
+use std::fmt::Display;
+use std::iter::{Product, Sum};
+use std::pin::Pin;
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+pub enum Null<T> {
+    Undefined,
+    Null,
+    Value(T),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum NullError {
+    Undefined,
+    Null,
+}
+
+impl Display for NullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NullError::Undefined => write!(f, "value was not provided"),
+            NullError::Null => write!(f, "value was explicitly set to null"),
+        }
+    }
+}
+
+impl std::error::Error for NullError {}
+
+pub type MergeFn<T> = Box<dyn FnOnce(Null<T>, Null<T>) -> Null<T>>;
+
+pub enum MergeStrategy<T> {
+    /// `other` wins whenever it is `Null` or `Value`; `Undefined` keeps `self`.
+    PreferOther,
+    /// `self` wins whenever it is `Null` or `Value`; `Undefined` falls back to `other`.
+    PreferDefined,
+    /// A `Value` on either side wins, `other`'s first. Otherwise `Null` wins over `Undefined`.
+    PreferValue,
+    /// Calls the function with `self` as the first argument and `other` as the second,
+    /// and returns its result.
+    Custom(MergeFn<T>),
+}
+
+impl<T: Display> Display for Null<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variant_str = match self {
+            Null::Undefined => "Undefined".to_string(),
+            Null::Null => "Null".to_string(),
+            Null::Value(value) => format!("Value({})", value)
+        };
+
+        write!(f, "{}", variant_str)
+    }
+}
+
+/// Returns the first `Value` among its arguments, like SQL `COALESCE`. `Null` and
+/// `Undefined` are skipped; if no argument holds a value the last one is returned.
+/// Arguments after the first `Value` are not evaluated.
+///
+/// ```
+/// let name: nulls::Null<&str> = nulls::coalesce!(nulls::null(), nulls::undefined(), nulls::new("anonymous"));
+/// assert_eq!(name, nulls::new("anonymous"));
+/// ```
+#[macro_export]
+macro_rules! coalesce {
+    ($last:expr $(,)?) => {
+        $last
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        match $first {
+            $crate::Null::Value(value) => $crate::Null::Value(value),
+            _ => $crate::coalesce!($($rest),+),
+        }
+    };
+}
+
+/// Unwraps a `Value`, or returns `Null` / `Undefined` early from the enclosing
+/// function, which must itself return a `Null`.
+///
+/// ```
+/// use nulls::{null_try, Null};
+///
+/// fn double(value: Null<i32>) -> Null<i32> {
+///     Null::Value(null_try!(value) * 2)
+/// }
+///
+/// assert_eq!(double(Null::Value(2)), Null::Value(4));
+/// assert_eq!(double(Null::Null), Null::Null);
+/// ```
+#[macro_export]
+macro_rules! null_try {
+    ($expr:expr $(,)?) => {
+        match $expr {
+            $crate::Null::Value(value) => value,
+            $crate::Null::Null => return $crate::Null::Null,
+            $crate::Null::Undefined => return $crate::Null::Undefined,
+        }
+    };
+}
+
+pub fn new<T>(value: T) -> Null<T> {
+    Null::Value(value)
+}
+
+pub fn undefined<T>() -> Null<T> {
+    Null::Undefined
+}
+
+pub fn null<T>() -> Null<T> {
+    Null::Null
+}
+
+#[allow(clippy::derivable_impls)]
+impl<T> Default for Null<T> {
+    fn default() -> Self {
+        Self::Undefined
+    }
+}
+
+impl<T> From<Null<T>> for Option<Option<T>> {
+    fn from(maybe_undefined: Null<T>) -> Self {
+        match maybe_undefined {
+            Null::Undefined => None,
+            Null::Null => Some(None),
+            Null::Value(value) => Some(Some(value)),
+        }
+    }
+}
+
+impl<T> From<Option<Option<T>>> for Null<T> {
+    fn from(value: Option<Option<T>>) -> Self {
+        match value {
+            Some(Some(value)) => Self::Value(value),
+            Some(None) => Self::Null,
+            None => Self::Undefined,
+        }
+    }
+}
+
+impl<T> IntoIterator for Null<T> {
+    type Item = T;
+    type IntoIter = std::option::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Null::Value(value) => Some(value),
+            _ => None,
+        }
+        .into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Null<T> {
+    type Item = &'a T;
+    type IntoIter = std::option::IntoIter<&'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Null<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::option::IntoIter<&'a mut T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+fn process<A, R, I, F>(iter: I, f: F) -> Null<R>
+where
+    I: IntoIterator<Item = Null<A>>,
+    F: FnOnce(&mut dyn Iterator<Item = A>) -> R,
+{
+    let mut residual = None;
+
+    let value = f(&mut iter.into_iter().map_while(|item| match item {
+        Null::Value(v) => Some(v),
+        Null::Null => {
+            residual = Some(Null::Null);
+            None
+        }
+        Null::Undefined => {
+            residual = Some(Null::Undefined);
+            None
+        }
+    }));
+
+    residual.unwrap_or(Null::Value(value))
+}
+
+/// Collects every `Value`, short-circuiting on the first `Null` or `Undefined`
+/// item, which then becomes the result.
+impl<A, V: FromIterator<A>> FromIterator<Null<A>> for Null<V> {
+    fn from_iter<I: IntoIterator<Item = Null<A>>>(iter: I) -> Self {
+        process(iter, |i| i.collect())
+    }
+}
+
+impl<T, U: Sum<T>> Sum<Null<T>> for Null<U> {
+    fn sum<I: Iterator<Item = Null<T>>>(iter: I) -> Self {
+        process(iter, |i| i.sum())
+    }
+}
+
+impl<T, U: Product<T>> Product<Null<T>> for Null<U> {
+    fn product<I: Iterator<Item = Null<T>>>(iter: I) -> Self {
+        process(iter, |i| i.product())
+    }
+}
+
+impl<T> From<Option<T>> for Null<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => Self::Value(value),
+            None => Self::Undefined,
+        }
+    }
+}
+
+impl<T> From<T> for Null<T> {
+    fn from(value: T) -> Self {
+        Self::Value(value)
+    }
+}
+
+/// `Undefined` and `Null` never equal a bare value.
+impl<T: PartialEq> PartialEq<T> for Null<T> {
+    fn eq(&self, other: &T) -> bool {
+        matches!(self, Null::Value(v) if v == other)
+    }
+}
+
+/// `Null` equals `None`, `Value` equals `Some`, and `Undefined` equals neither.
+impl<T: PartialEq> PartialEq<Option<T>> for Null<T> {
+    fn eq(&self, other: &Option<T>) -> bool {
+        match (self, other) {
+            (Null::Value(a), Some(b)) => a == b,
+            (Null::Null, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq<Null<T>> for Option<T> {
+    fn eq(&self, other: &Null<T>) -> bool {
+        other == self
+    }
+}
+
+// Blanket `impl<T> TryFrom<Null<T>> for T` and `impl<T> PartialEq<Null<T>> for T`
+// are rejected by the orphan rules, so those directions are provided for common
+// concrete types.
+macro_rules! impl_concrete {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl TryFrom<Null<$ty>> for $ty {
+                type Error = NullError;
+
+                fn try_from(value: Null<$ty>) -> Result<Self, Self::Error> {
+                    value.require()
+                }
+            }
+
+            impl PartialEq<Null<$ty>> for $ty {
+                fn eq(&self, other: &Null<$ty>) -> bool {
+                    other == self
+                }
+            }
+        )*
+    };
+}
+
+impl_concrete!(
+    bool, char, String,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+    f32, f64,
+);
+
+#[cfg(feature = "serde")]
+impl_concrete!(serde_json::Value);
+
+impl<T> Null<T> {
+    /// Unlike `From<Option<T>>`, maps `None` to an explicit `Null`.
+    pub fn from_option(value: Option<T>) -> Null<T> {
+        match value {
+            Some(value) => Null::Value(value),
+            None => Null::Null,
+        }
+    }
+
+    /// Borrowing counterpart of `From<Option<T>>`; `None` maps to `Undefined`.
+    pub const fn from_ref(value: &Option<T>) -> Null<&T> {
+        match value {
+            Some(value) => Null::Value(value),
+            None => Null::Undefined,
+        }
+    }
+
+    /// Borrowing counterpart of `From<Option<Option<T>>>`.
+    pub const fn from_double_ref(value: &Option<Option<T>>) -> Null<&T> {
+        match value {
+            Some(Some(value)) => Null::Value(value),
+            Some(None) => Null::Null,
+            None => Null::Undefined,
+        }
+    }
+
+    pub fn to_option_cloned(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.value().cloned()
+    }
+
+    /// Reads a map entry, treating an absent key as `Undefined`.
+    pub fn from_entry(entry: Option<&Null<T>>) -> Null<&T> {
+        entry.map_or(Null::Undefined, Null::as_ref)
+    }
+
+    pub fn from_double_option(value: Option<Option<T>>) -> Null<T> {
+        value.into()
+    }
+
+    pub fn into_double_option(self) -> Option<Option<T>> {
+        self.into()
+    }
+
+    pub const fn is_undefined(&self) -> bool {
+        matches!(self, Null::Undefined)
+    }
+
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Null::Null)
+    }
+
+    pub const fn is_value(&self) -> bool {
+        matches!(self, Null::Value(_))
+    }
+
+    pub fn is_value_and<F: FnOnce(T) -> bool>(self, f: F) -> bool {
+        match self {
+            Null::Value(v) => f(v),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` for `Null`, or for a `Value` matching `f`. `Undefined` is `false`.
+    pub fn is_null_or<F: FnOnce(T) -> bool>(self, f: F) -> bool {
+        match self {
+            Null::Value(v) => f(v),
+            Null::Null => true,
+            Null::Undefined => false,
+        }
+    }
+
+    /// Returns `true` for `Undefined`, or for a `Value` matching `f`. `Null` is `false`.
+    pub fn is_undefined_or<F: FnOnce(T) -> bool>(self, f: F) -> bool {
+        match self {
+            Null::Value(v) => f(v),
+            Null::Null => false,
+            Null::Undefined => true,
+        }
+    }
+
+    pub const fn value(&self) -> Option<&T> {
+        match self {
+            Null::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Null::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub const fn as_option_ref(&self) -> Option<Option<&T>> {
+        match self {
+            Null::Value(value) => Some(Some(value)),
+            Null::Null => Some(None),
+            Null::Undefined => None,
+        }
+    }
+
+    #[track_caller]
+    pub fn expect(self, msg: &str) -> T {
+        match self {
+            Null::Value(value) => value,
+            Null::Null => panic!("{}: value is `Null`", msg),
+            Null::Undefined => panic!("{}: value is `Undefined`", msg),
+        }
+    }
+
+    #[track_caller]
+    pub fn unwrap(self) -> T {
+        match self {
+            Null::Value(value) => value,
+            Null::Null => panic!("called `Null::unwrap()` on a `Null` value"),
+            Null::Undefined => panic!("called `Null::unwrap()` on an `Undefined` value"),
+        }
+    }
+
+    pub fn unwrap_or(self, default: T) -> T {
+        match self {
+            Null::Value(value) => value,
+            _ => default,
+        }
+    }
+
+    pub fn unwrap_or_else<F: FnOnce() -> T>(self, f: F) -> T {
+        match self {
+            Null::Value(value) => value,
+            _ => f(),
+        }
+    }
+
+    pub fn unwrap_or_default(self) -> T
+    where
+        T: Default,
+    {
+        match self {
+            Null::Value(value) => value,
+            _ => T::default(),
+        }
+    }
+
+    pub const fn as_ref(&self) -> Null<&T> {
+        match self {
+            Null::Value(value) => Null::Value(value),
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+
+    pub fn as_mut(&mut self) -> Null<&mut T> {
+        match self {
+            Null::Value(value) => Null::Value(value),
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+
+    pub fn as_pin_ref(self: Pin<&Self>) -> Null<Pin<&T>> {
+        // SAFETY: `x` is guaranteed to be pinned because it comes from `self` which is pinned.
+        self.get_ref().as_ref().map_value(|x| unsafe { Pin::new_unchecked(x) })
+    }
+
+    pub fn as_pin_mut(self: Pin<&mut Self>) -> Null<Pin<&mut T>> {
+        // SAFETY: `get_unchecked_mut` is never used to move the `Null` inside `self`.
+        // `x` is guaranteed to be pinned because it comes from `self` which is pinned.
+        unsafe { self.get_unchecked_mut().as_mut().map_value(|x| Pin::new_unchecked(x)) }
+    }
+
+    pub const fn as_slice(&self) -> &[T] {
+        match self {
+            Null::Value(value) => std::slice::from_ref(value),
+            _ => &[],
+        }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match self {
+            Null::Value(value) => std::slice::from_mut(value),
+            _ => &mut [],
+        }
+    }
+
+    pub fn iter(&self) -> std::option::IntoIter<&T> {
+        self.value().into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::option::IntoIter<&mut T> {
+        self.value_mut().into_iter()
+    }
+
+    /// Takes the current variant out, leaving `Undefined` in its place.
+    pub fn take(&mut self) -> Null<T> {
+        std::mem::take(self)
+    }
+
+    /// Replaces the current variant with `Value(value)`, returning the old one.
+    pub fn replace(&mut self, value: T) -> Null<T> {
+        std::mem::replace(self, Null::Value(value))
+    }
+
+    pub fn insert(&mut self, value: T) -> &mut T {
+        *self = Null::Value(value);
+
+        match self {
+            Null::Value(value) => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Inserts `value` if the slot is `Null` or `Undefined`, then returns a mutable reference to it.
+    pub fn get_or_insert(&mut self, value: T) -> &mut T {
+        self.get_or_insert_with(|| value)
+    }
+
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, f: F) -> &mut T {
+        if !self.is_value() {
+            *self = Null::Value(f());
+        }
+
+        match self {
+            Null::Value(value) => value,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if `self` is a `Value` equal to `other`.
+    ///
+    /// ```
+    /// let name = nulls::new(String::from("nulls"));
+    ///
+    /// assert!(name.eq_value("nulls"));
+    /// assert!(name.eq_value(&String::from("nulls")));
+    /// assert!(!nulls::null::<String>().eq_value("nulls"));
+    /// ```
+    pub fn eq_value<U>(&self, other: &U) -> bool
+    where
+        T: PartialEq<U>,
+        U: ?Sized,
+    {
+        matches!(self, Null::Value(v) if v == other)
+    }
+
+    /// Returns `true` if `self` is a `Value` equal to `Some`, or `Null` and `other` is `None`.
+    /// `Undefined` never matches.
+    ///
+    /// ```
+    /// let name = nulls::new("nulls");
+    ///
+    /// assert!(name.matches_option(&Some(String::from("nulls"))));
+    /// assert!(nulls::null::<&str>().matches_option::<String>(&None));
+    /// assert!(!nulls::undefined::<&str>().matches_option::<String>(&None));
+    /// ```
+    pub fn matches_option<U>(&self, other: &Option<U>) -> bool
+    where
+        T: PartialEq<U>,
+    {
+        match self {
+            Null::Value(v) => matches!(other, Some(u) if v == u),
+            Null::Null => other.is_none(),
+            Null::Undefined => false,
+        }
+    }
+
+    #[deprecated(note = "use `eq_value` instead")]
+    pub fn contains_value<U>(&self, x: &U) -> bool
+    where
+        U: PartialEq<T>,
+    {
+        match self {
+            Null::Value(y) => x == y,
+            _ => false,
+        }
+    }
+
+    #[deprecated(note = "use `matches_option` instead")]
+    pub fn contains<U>(&self, x: &Option<U>) -> bool
+    where
+        U: PartialEq<T>,
+    {
+        match self {
+            Null::Value(y) => matches!(x, Some(v) if v == y),
+            Null::Null => x.is_none(),
+            Null::Undefined => false,
+        }
+    }
+
+    pub fn map<U, F: FnOnce(Option<T>) -> Option<U>>(self, f: F) -> Null<U> {
+        match self {
+            Null::Value(v) => match f(Some(v)) {
+                Some(v) => Null::Value(v),
+                None => Null::Null,
+            },
+            Null::Null => match f(None) {
+                Some(v) => Null::Value(v),
+                None => Null::Null,
+            },
+            Null::Undefined => Null::Undefined,
+        }
+    }
+
+    pub fn map_value<U, F: FnOnce(T) -> U>(self, f: F) -> Null<U> {
+        match self {
+            Null::Value(v) => Null::Value(f(v)),
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+
+    pub fn map_or<U, F: FnOnce(T) -> U>(self, default: U, f: F) -> U {
+        match self {
+            Null::Value(v) => f(v),
+            _ => default,
+        }
+    }
+
+    /// Collapses every variant into a `U`, with separate fallbacks for `Undefined` and `Null`.
+    pub fn map_or_else<U, D, N, F>(self, undefined: D, null: N, f: F) -> U
+    where
+        D: FnOnce() -> U,
+        N: FnOnce() -> U,
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Null::Value(v) => f(v),
+            Null::Null => null(),
+            Null::Undefined => undefined(),
+        }
+    }
+
+    /// Total eliminator handling all three variants in one expression.
+    pub fn fold<U, F: FnOnce(T) -> U>(self, undefined: U, null: U, f: F) -> U {
+        match self {
+            Null::Value(v) => f(v),
+            Null::Null => null,
+            Null::Undefined => undefined,
+        }
+    }
+
+    /// Two-way collapse where `Null` and `Undefined` both call `on_missing`.
+    pub fn either<U, M, F>(self, on_missing: M, on_value: F) -> U
+    where
+        M: FnOnce() -> U,
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Null::Value(v) => on_value(v),
+            _ => on_missing(),
+        }
+    }
+
+    pub fn inspect<F: FnOnce(&T)>(self, f: F) -> Self {
+        if let Null::Value(ref v) = self {
+            f(v);
+        }
+
+        self
+    }
+
+    pub fn inspect_null<F: FnOnce()>(self, f: F) -> Self {
+        if self.is_null() {
+            f();
+        }
+
+        self
+    }
+
+    pub fn inspect_undefined<F: FnOnce()>(self, f: F) -> Self {
+        if self.is_undefined() {
+            f();
+        }
+
+        self
+    }
+
+    pub fn ok_or<E>(self, err: E) -> Result<T, E> {
+        match self {
+            Null::Value(v) => Ok(v),
+            _ => Err(err),
+        }
+    }
+
+    pub fn ok_or_else<E, F: FnOnce() -> E>(self, f: F) -> Result<T, E> {
+        match self {
+            Null::Value(v) => Ok(v),
+            _ => Err(f()),
+        }
+    }
+
+    /// Requires a concrete value, reporting whether it was cleared or never provided.
+    pub fn require(self) -> Result<T, NullError> {
+        match self {
+            Null::Value(v) => Ok(v),
+            Null::Null => Err(NullError::Null),
+            Null::Undefined => Err(NullError::Undefined),
+        }
+    }
+
+    /// Returns `other` if `self` is a `Value`, otherwise propagates `Null` or `Undefined`.
+    pub fn and<U>(self, other: Null<U>) -> Null<U> {
+        match self {
+            Null::Value(_) => other,
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+
+    /// Calls `f` with the contained value, otherwise propagates `Null` or `Undefined`.
+    pub fn and_then<U, F: FnOnce(T) -> Null<U>>(self, f: F) -> Null<U> {
+        match self {
+            Null::Value(v) => f(v),
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+
+    /// Returns `self` if it is a `Value`, otherwise `other`. An explicit `Null`
+    /// is kept over an `Undefined` fallback.
+    pub fn or(self, other: Null<T>) -> Null<T> {
+        match (self, other) {
+            (Null::Value(v), _) => Null::Value(v),
+            (Null::Null, Null::Undefined) => Null::Null,
+            (_, other) => other,
+        }
+    }
+
+    /// Returns `self` if it is a `Value`, otherwise `other`, treating `Null` and
+    /// `Undefined` alike. See [`coalesce!`] for the variadic form.
+    pub fn or_value(self, other: Null<T>) -> Null<T> {
+        match self {
+            Null::Value(v) => Null::Value(v),
+            _ => other,
+        }
+    }
+
+    pub fn swap_null_undefined(self) -> Null<T> {
+        match self {
+            Null::Null => Null::Undefined,
+            Null::Undefined => Null::Null,
+            value => value,
+        }
+    }
+
+    pub fn null_to_undefined(self) -> Null<T> {
+        match self {
+            Null::Null => Null::Undefined,
+            other => other,
+        }
+    }
+
+    pub fn undefined_to_null(self) -> Null<T> {
+        match self {
+            Null::Undefined => Null::Null,
+            other => other,
+        }
+    }
+
+    /// Fills in `value` only when nothing was sent; an explicit `Null` is preserved.
+    pub fn replace_if_undefined(self, value: T) -> Null<T> {
+        match self {
+            Null::Undefined => Null::Value(value),
+            other => other,
+        }
+    }
+
+    /// Overrides an explicit `Null` with `T::default()`; `Undefined` is preserved.
+    pub fn default_if_null(self) -> Null<T>
+    where
+        T: Default,
+    {
+        match self {
+            Null::Null => Null::Value(T::default()),
+            other => other,
+        }
+    }
+
+    /// Lazy version of [`Null::or`]; `f` is only called when `self` is not a `Value`.
+    pub fn or_else<F: FnOnce() -> Null<T>>(self, f: F) -> Null<T> {
+        match self {
+            Null::Value(v) => Null::Value(v),
+            Null::Null => match f() {
+                Null::Undefined => Null::Null,
+                other => other,
+            },
+            Null::Undefined => f(),
+        }
+    }
+
+    /// Returns the `Value` if exactly one side holds one. Otherwise returns `Null`
+    /// if either side is defined, and `Undefined` only if both are `Undefined`.
+    pub fn xor(self, other: Null<T>) -> Null<T> {
+        match (self, other) {
+            (Null::Value(v), Null::Null | Null::Undefined) => Null::Value(v),
+            (Null::Null | Null::Undefined, Null::Value(v)) => Null::Value(v),
+            (Null::Undefined, Null::Undefined) => Null::Undefined,
+            _ => Null::Null,
+        }
+    }
+
+    /// Turns a `Value` into `Null` when `predicate` returns `false`.
+    /// `Null` and `Undefined` are returned unchanged.
+    pub fn filter<P: FnOnce(&T) -> bool>(self, predicate: P) -> Null<T> {
+        match self {
+            Null::Value(v) if predicate(&v) => Null::Value(v),
+            Null::Value(_) => Null::Null,
+            other => other,
+        }
+    }
+
+    /// Takes the value out when `predicate` returns `true`, leaving `Undefined` in its place.
+    pub fn take_if<P: FnOnce(&mut T) -> bool>(&mut self, predicate: P) -> Option<T> {
+        let taken = match self {
+            Null::Value(v) => predicate(v),
+            _ => false,
+        };
+
+        if !taken {
+            return None;
+        }
+
+        match self.take() {
+            Null::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Combines two `Null`s into a `Value` pair when both hold values.
+    /// Otherwise an explicit `Null` on either side wins over `Undefined`.
+    pub fn zip<U>(self, other: Null<U>) -> Null<(T, U)> {
+        self.zip_with(other, |a, b| (a, b))
+    }
+
+    /// Like [`Null::zip`], but combines the two values with `f`.
+    pub fn zip_with<U, R, F: FnOnce(T, U) -> R>(self, other: Null<U>, f: F) -> Null<R> {
+        match (self, other) {
+            (Null::Value(a), Null::Value(b)) => Null::Value(f(a, b)),
+            (Null::Null, _) | (_, Null::Null) => Null::Null,
+            _ => Null::Undefined,
+        }
+    }
+
+    pub fn merge(self, other: Null<T>, strategy: MergeStrategy<T>) -> Null<T> {
+        match strategy {
+            MergeStrategy::PreferOther => match other {
+                Null::Undefined => self,
+                other => other,
+            },
+            MergeStrategy::PreferDefined => match self {
+                Null::Undefined => other,
+                this => this,
+            },
+            MergeStrategy::PreferValue => other.or(self),
+            MergeStrategy::Custom(f) => f(self, other),
+        }
+    }
+
+    pub fn update_to(self, value: &mut Option<T>) {
+        match self {
+            Null::Value(new) => *value = Some(new),
+            Null::Null => *value = None,
+            Null::Undefined => {}
+        };
+    }
+}
+
+impl<T: Clone + PartialEq> Null<T> {
+    /// Computes the patch turning `old` into `new`: `Undefined` when unchanged,
+    /// `Null` when cleared and `Value` when set.
+    pub fn from_change(old: &Option<T>, new: &Option<T>) -> Null<T> {
+        match new {
+            _ if old == new => Null::Undefined,
+            Some(value) => Null::Value(value.clone()),
+            None => Null::Null,
+        }
+    }
+}
+
+impl<T: Clone> Null<&T> {
+    pub fn cloned(self) -> Null<T> {
+        self.map_value(T::clone)
+    }
+}
+
+impl<T: Copy> Null<&T> {
+    pub fn copied(self) -> Null<T> {
+        self.map_value(|&v| v)
+    }
+}
+
+impl<T: Clone> Null<&mut T> {
+    pub fn cloned(self) -> Null<T> {
+        self.map_value(|v| v.clone())
+    }
+}
+
+impl<T: Copy> Null<&mut T> {
+    pub fn copied(self) -> Null<T> {
+        self.map_value(|&mut v| v)
+    }
+}
+
+impl<A, B> Null<(A, B)> {
+    /// Splits a pair, copying `Null` or `Undefined` to both sides.
+    pub fn unzip(self) -> (Null<A>, Null<B>) {
+        match self {
+            Null::Value((a, b)) => (Null::Value(a), Null::Value(b)),
+            Null::Null => (Null::Null, Null::Null),
+            Null::Undefined => (Null::Undefined, Null::Undefined),
+        }
+    }
+}
+
+impl<T> Null<Null<T>> {
+    /// Collapses one level of nesting. The outer `Undefined` or `Null` wins,
+    /// otherwise the inner variant is returned as is.
+    pub fn flatten(self) -> Null<T> {
+        match self {
+            Null::Value(inner) => inner,
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+}
+
+impl<T> Null<Option<T>> {
+    /// Collapses an inner `Option`. The outer `Undefined` or `Null` wins,
+    /// and an inner `None` becomes `Null`.
+    pub fn flatten_option(self) -> Null<T> {
+        match self {
+            Null::Value(Some(v)) => Null::Value(v),
+            Null::Value(None) | Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+}
+
+impl<T, E> Null<Result<T, E>> {
+    pub fn transpose(self) -> Result<Null<T>, E> {
+        match self {
+            Null::Value(Ok(v)) => Ok(Null::Value(v)),
+            Null::Value(Err(e)) => Err(e),
+            Null::Null => Ok(Null::Null),
+            Null::Undefined => Ok(Null::Undefined),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T> std::ops::Try for Null<T> {
+    type Output = T;
+    type Residual = Null<std::convert::Infallible>;
+
+    fn from_output(output: T) -> Self {
+        Null::Value(output)
+    }
+
+    fn branch(self) -> std::ops::ControlFlow<Self::Residual, T> {
+        match self {
+            Null::Value(v) => std::ops::ControlFlow::Continue(v),
+            Null::Null => std::ops::ControlFlow::Break(Null::Null),
+            Null::Undefined => std::ops::ControlFlow::Break(Null::Undefined),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T> std::ops::FromResidual<Null<std::convert::Infallible>> for Null<T> {
+    fn from_residual(residual: Null<std::convert::Infallible>) -> Self {
+        match residual {
+            Null::Value(never) => match never {},
+            Null::Null => Null::Null,
+            Null::Undefined => Null::Undefined,
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T> std::ops::Residual<T> for Null<std::convert::Infallible> {
+    type TryType = Null<T>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MergeStrategy;
+    use crate::Null::{self, Null as N, Undefined as U, Value as V};
+
+    /// Checks `op` against a table of `(self, other, expected)` rows covering every pair
+    /// of states.
+    fn check<R: PartialEq + std::fmt::Debug>(
+        name: &str,
+        op: impl Fn(Null<i32>, Null<i32>) -> R,
+        table: [(Null<i32>, Null<i32>, R); 9],
+    ) {
+        for (this, other, expected) in table {
+            assert_eq!(op(this, other), expected, "{this:?}.{name}({other:?})");
+        }
+    }
+
+    #[test]
+    fn and() {
+        check("and", Null::and, [
+            (V(1), V(2), V(2)),
+            (V(1), N, N),
+            (V(1), U, U),
+            (N, V(2), N),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), U),
+            (U, N, U),
+            (U, U, U),
+        ]);
+    }
+
+    #[test]
+    fn or() {
+        let table = [
+            (V(1), V(2), V(1)),
+            (V(1), N, V(1)),
+            (V(1), U, V(1)),
+            (N, V(2), V(2)),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), V(2)),
+            (U, N, N),
+            (U, U, U),
+        ];
+
+        check("or", Null::or, table);
+        check("or_else", |this, other| this.or_else(|| other), table);
+    }
+
+    #[test]
+    fn xor() {
+        check("xor", Null::xor, [
+            (V(1), V(2), N),
+            (V(1), N, V(1)),
+            (V(1), U, V(1)),
+            (N, V(2), V(2)),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), V(2)),
+            (U, N, N),
+            (U, U, U),
+        ]);
+    }
+
+    #[test]
+    fn take_if() {
+        let table = [
+            (V(1), true, Some(1), U),
+            (V(1), false, None, V(1)),
+            (N, true, None, N),
+            (N, false, None, N),
+            (U, true, None, U),
+            (U, false, None, U),
+        ];
+
+        for (before, predicate, taken, after) in table {
+            let mut null = before;
+            assert_eq!(null.take_if(|_| predicate), taken, "{before:?}.take_if(|_| {predicate})");
+            assert_eq!(null, after, "{before:?}.take_if(|_| {predicate})");
+        }
+    }
+
+    #[test]
+    fn zip() {
+        check("zip", Null::zip, [
+            (V(1), V(2), V((1, 2))),
+            (V(1), N, N),
+            (V(1), U, U),
+            (N, V(2), N),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), U),
+            (U, N, N),
+            (U, U, U),
+        ]);
+    }
+
+    #[test]
+    fn flatten() {
+        let table: [(Null<Null<i32>>, Null<i32>); 5] = [
+            (V(V(1)), V(1)),
+            (V(N), N),
+            (V(U), U),
+            (N, N),
+            (U, U),
+        ];
+
+        for (nested, expected) in table {
+            assert_eq!(nested.flatten(), expected, "{nested:?}.flatten()");
+        }
+    }
+
+    #[test]
+    fn merge() {
+        check("merge(PreferOther)", |this, other| this.merge(other, MergeStrategy::PreferOther), [
+            (V(1), V(2), V(2)),
+            (V(1), N, N),
+            (V(1), U, V(1)),
+            (N, V(2), V(2)),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), V(2)),
+            (U, N, N),
+            (U, U, U),
+        ]);
+
+        check("merge(PreferDefined)", |this, other| this.merge(other, MergeStrategy::PreferDefined), [
+            (V(1), V(2), V(1)),
+            (V(1), N, V(1)),
+            (V(1), U, V(1)),
+            (N, V(2), N),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), V(2)),
+            (U, N, N),
+            (U, U, U),
+        ]);
+
+        check("merge(PreferValue)", |this, other| this.merge(other, MergeStrategy::PreferValue), [
+            (V(1), V(2), V(2)),
+            (V(1), N, V(1)),
+            (V(1), U, V(1)),
+            (N, V(2), V(2)),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), V(2)),
+            (U, N, N),
+            (U, U, U),
+        ]);
+
+        // `Custom` receives `self` first and `other` second; `and` tells the two apart.
+        check("merge(Custom)", |this, other| this.merge(other, MergeStrategy::Custom(Box::new(Null::and))), [
+            (V(1), V(2), V(2)),
+            (V(1), N, N),
+            (V(1), U, U),
+            (N, V(2), N),
+            (N, N, N),
+            (N, U, N),
+            (U, V(2), U),
+            (U, N, U),
+            (U, U, U),
+        ]);
+    }
+}
@@ -0,0 +1,103 @@
+//! Diesel conversions for `Nullable<ST>` columns.
+//!
+//! `Null<T>` loads from and binds to any `Nullable<ST>` column that `T` handles as `ST`,
+//! reading NULL as `Null`. A bound value cannot be skipped, so `Undefined` binds as NULL;
+//! build changesets with [`Null::assign`] to leave `Undefined` columns out instead.
+
+use ::diesel::backend::Backend;
+use ::diesel::deserialize::{self, FromSql, Queryable};
+use ::diesel::dsl;
+use ::diesel::expression::{AsExpression, Expression, TypedExpressionType};
+use ::diesel::serialize::{self, IsNull, Output, ToSql};
+use ::diesel::sql_types::{is_nullable, Nullable, SingleValue, SqlType};
+use ::diesel::ExpressionMethods;
+
+use crate::Null;
+
+impl<T, ST, DB> FromSql<Nullable<ST>, DB> for Null<T>
+where
+    T: FromSql<ST, DB>,
+    DB: Backend,
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        T::from_sql(bytes).map(Null::Value)
+    }
+
+    fn from_nullable_sql(bytes: Option<DB::RawValue<'_>>) -> deserialize::Result<Self> {
+        match bytes {
+            Some(bytes) => T::from_sql(bytes).map(Null::Value),
+            None => Ok(Null::Null),
+        }
+    }
+}
+
+impl<T, ST, DB> ToSql<Nullable<ST>, DB> for Null<T>
+where
+    T: ToSql<ST, DB>,
+    DB: Backend,
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        match self {
+            Null::Value(ref value) => value.to_sql(out),
+            Null::Undefined | Null::Null => Ok(IsNull::Yes),
+        }
+    }
+}
+
+impl<T, ST> AsExpression<Nullable<ST>> for Null<T>
+where
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+    Nullable<ST>: TypedExpressionType,
+{
+    type Expression = <Option<T> as AsExpression<Nullable<ST>>>::Expression;
+
+    fn as_expression(self) -> Self::Expression {
+        self.into_double_option().flatten().as_expression()
+    }
+}
+
+impl<'a, T, ST> AsExpression<Nullable<ST>> for &'a Null<T>
+where
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+    Nullable<ST>: TypedExpressionType,
+{
+    type Expression = <Option<&'a T> as AsExpression<Nullable<ST>>>::Expression;
+
+    fn as_expression(self) -> Self::Expression {
+        self.value().as_expression()
+    }
+}
+
+impl<T, ST, DB> Queryable<ST, DB> for Null<T>
+where
+    ST: SingleValue<IsNull = is_nullable::IsNullable>,
+    DB: Backend,
+    Self: FromSql<ST, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self::Row) -> deserialize::Result<Self> {
+        Ok(row)
+    }
+}
+
+impl<T> Null<T> {
+    /// Returns `column.eq(self)`, or `None` for `Undefined`.
+    ///
+    /// `Option` changesets are skipped when `None`, so a tuple of these passed to
+    /// `diesel::update(...).set(...)` only assigns the provided columns, including
+    /// explicit NULLs, which an `Option` field alone cannot express.
+    pub fn assign<C>(self, column: C) -> Option<dsl::Eq<C, Self>>
+    where
+        C: Expression + ExpressionMethods,
+        C::SqlType: SingleValue,
+        Self: AsExpression<C::SqlType>,
+    {
+        match self {
+            Null::Undefined => None,
+            value => Some(column.eq(value)),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! JSON Patch ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)) for flat patch structs.
+//!
+//! A patch struct, such as one generated by `#[derive(Patch)]`, converts to one operation
+//! per defined field: `Value` becomes `replace` and `Null` becomes `remove`, while
+//! `Undefined` fields produce nothing. The importer goes the other way and accepts `add`,
+//! `replace` and `remove` on top-level members.
+//!
+//! ```
+//! use nulls::json_patch::{self, Operation};
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//! use serde_json::json;
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct UserPatch {
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     name: Null<String>,
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     bio: Null<String>,
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     email: Null<String>,
+//! }
+//!
+//! let patch = UserPatch { name: nulls::new("nulls".into()), bio: Null::Null, email: Null::Undefined };
+//! let operations = json_patch::to_operations(&patch).unwrap();
+//!
+//! assert_eq!(
+//!     serde_json::to_value(&operations).unwrap(),
+//!     json!([
+//!         { "op": "replace", "path": "/name", "value": "nulls" },
+//!         { "op": "remove", "path": "/bio" },
+//!     ]),
+//! );
+//!
+//! let parsed: UserPatch = json_patch::from_operations(&operations).unwrap();
+//! assert_eq!(parsed, patch);
+//! ```
+
+use ::serde::de::DeserializeOwned;
+use ::serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt::Display;
+
+/// The subset of RFC 6902 operations that a flat patch can express.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+impl Operation {
+    pub fn path(&self) -> &str {
+        match self {
+            Operation::Add { path, .. } | Operation::Remove { path } | Operation::Replace { path, .. } => path,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum JsonPatchError {
+    /// The patch did not serialize to a JSON object.
+    NotAnObject,
+    /// The path does not point at a top-level member, such as `/name`.
+    Path(String),
+    Json(serde_json::Error),
+}
+
+impl Display for JsonPatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonPatchError::NotAnObject => write!(f, "patch must serialize to a JSON object"),
+            JsonPatchError::Path(path) => write!(f, "path `{}` does not point at a top-level member", path),
+            JsonPatchError::Json(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for JsonPatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonPatchError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonPatchError {
+    fn from(error: serde_json::Error) -> Self {
+        JsonPatchError::Json(error)
+    }
+}
+
+/// Converts a patch into JSON Patch operations, in the order its fields serialize.
+///
+/// `Null` becomes `remove`, which RFC 6902 rejects when the member does not exist in the
+/// target, and `Value` becomes `replace`, which has the same requirement. Fields must
+/// skip `Undefined` when serialized, otherwise they are written as `Null`.
+pub fn to_operations<P>(patch: &P) -> Result<Vec<Operation>, JsonPatchError>
+where
+    P: Serialize + ?Sized,
+{
+    let Value::Object(fields) = serde_json::to_value(patch)? else {
+        return Err(JsonPatchError::NotAnObject);
+    };
+
+    let operations = fields
+        .into_iter()
+        .map(|(key, value)| {
+            let path = format!("/{}", escape(&key));
+
+            match value {
+                Value::Null => Operation::Remove { path },
+                value => Operation::Replace { path, value },
+            }
+        })
+        .collect();
+
+    Ok(operations)
+}
+
+/// Builds a patch from JSON Patch operations on top-level members. `add` and `replace`
+/// become `Value`, `remove` becomes `Null` and members without an operation stay
+/// `Undefined`. When a member appears twice, the last operation wins.
+///
+/// A JSON `null` value is read the same way a patch reads `null` anywhere else, so `add`
+/// or `replace` with `null` becomes `Null` exactly like `remove`, even for a
+/// `Null<serde_json::Value>` field. RFC 6902 would instead set the member to `null`; a
+/// flat patch has no way to tell the two apart, just as [`to_operations`] writes
+/// `Value(serde_json::Value::Null)` as `remove`.
+///
+/// ```
+/// use nulls::json_patch::{self, Operation};
+/// use nulls::Null;
+/// use serde::Deserialize;
+/// use serde_json::Value;
+///
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// struct SettingsPatch {
+///     #[serde(default)]
+///     theme: Null<Value>,
+/// }
+///
+/// let operations = [Operation::Add { path: "/theme".into(), value: Value::Null }];
+/// let parsed: SettingsPatch = json_patch::from_operations(&operations).unwrap();
+/// assert_eq!(parsed.theme, Null::Null);
+/// ```
+pub fn from_operations<P>(operations: &[Operation]) -> Result<P, JsonPatchError>
+where
+    P: DeserializeOwned,
+{
+    let mut fields = Map::new();
+
+    for operation in operations {
+        let key = member(operation.path())?;
+
+        let value = match operation {
+            Operation::Add { value, .. } | Operation::Replace { value, .. } => value.clone(),
+            Operation::Remove { .. } => Value::Null,
+        };
+
+        fields.insert(key, value);
+    }
+
+    Ok(serde_json::from_value(Value::Object(fields))?)
+}
+
+/// Escapes a member name as a JSON Pointer reference token (RFC 6901).
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Returns the member name of a single-token JSON Pointer such as `/name`.
+fn member(path: &str) -> Result<String, JsonPatchError> {
+    match path.strip_prefix('/') {
+        Some(token) if !token.contains('/') => Ok(token.replace("~1", "/").replace("~0", "~")),
+        _ => Err(JsonPatchError::Path(path.to_owned())),
+    }
+}
@@ -0,0 +1,44 @@
+//! libsql / Turso conversions.
+//!
+//! `Null<T>` binds as a parameter wherever `T` does, with `Null` and `Undefined` both
+//! written as NULL. Rows read through `libsql::de::from_row` fill `Null` fields from
+//! SQL NULL as `Null`.
+//!
+//! ```
+//! use libsql::Value;
+//! use nulls::Null;
+//!
+//! assert_eq!(Value::from(nulls::new("nulls")), Value::Text("nulls".into()));
+//! assert_eq!(Value::from(Null::<i64>::Undefined), Value::Null);
+//!
+//! assert_eq!(Null::<i64>::try_from_libsql_value(Value::Integer(7)).unwrap(), Null::Value(7));
+//! assert_eq!(Null::<i64>::try_from_libsql_value(Value::Null).unwrap(), Null::Null);
+//! ```
+
+use ::libsql::Value;
+use ::serde::de::value::Error;
+use ::serde::de::{DeserializeOwned, IntoDeserializer};
+
+use crate::Null;
+
+impl<T: Into<Value>> From<Null<T>> for Value {
+    fn from(value: Null<T>) -> Value {
+        match value {
+            Null::Value(value) => value.into(),
+            Null::Undefined | Null::Null => Value::Null,
+        }
+    }
+}
+
+impl<T> Null<T> {
+    /// Reads a column value, mapping SQL NULL to `Null`.
+    pub fn try_from_libsql_value(value: Value) -> Result<Null<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        match value {
+            Value::Null => Ok(Null::Null),
+            value => T::deserialize(value.into_deserializer()).map(Null::Value),
+        }
+    }
+}
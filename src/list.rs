@@ -0,0 +1,246 @@
+//! Partial updates for lists.
+//!
+//! Merge patches can only replace a list as a whole. A [`ListPatch`] either does that or
+//! carries a sequence of [`ListOp`]s: appends, positional inserts, sets and removals, and
+//! key-based removals and upserts, where the key of an element comes from a closure such as
+//! `|item: &Tag| item.id`. It can sit inside a `Null<ListPatch<T>>` field like any other
+//! value.
+//!
+//! ```
+//! use nulls::list::{ListOp, ListPatch};
+//!
+//! #[derive(Clone, PartialEq, Debug)]
+//! struct Tag {
+//!     id: u32,
+//!     name: &'static str,
+//! }
+//!
+//! let mut tags = vec![Tag { id: 1, name: "a" }, Tag { id: 2, name: "b" }];
+//!
+//! let patch = ListPatch::Ops(vec![
+//!     ListOp::RemoveKey(1),
+//!     ListOp::Upsert(Tag { id: 2, name: "B" }),
+//!     ListOp::Append(vec![Tag { id: 3, name: "c" }]),
+//! ]);
+//!
+//! patch.apply_by(&mut tags, |tag| tag.id).unwrap();
+//! assert_eq!(tags, [Tag { id: 2, name: "B" }, Tag { id: 3, name: "c" }]);
+//!
+//! let old = vec![1, 2, 3];
+//! assert_eq!(ListPatch::diff(&old, &[1, 3, 4]), ListPatch::Ops(vec![ListOp::RemoveKey(2), ListOp::Upsert(4)]));
+//! assert_eq!(ListPatch::diff(&old, &[3, 2, 1]), ListPatch::Replace(vec![3, 2, 1]));
+//! ```
+//!
+//! With the `serde` feature, `Replace` is written as a plain array and `Ops` as
+//! `{"ops": [...]}`, with one single-key object per operation:
+//!
+//! ```
+//! use nulls::list::{ListOp, ListPatch};
+//!
+//! let patch: ListPatch<i32> = serde_json::from_str(r#"{"ops":[{"append":[3]},{"remove":0}]}"#).unwrap();
+//! assert_eq!(patch, ListPatch::Ops(vec![ListOp::Append(vec![3]), ListOp::Remove(0)]));
+//!
+//! let mut list = vec![1, 2];
+//! patch.apply(&mut list).unwrap();
+//! assert_eq!(list, [2, 3]);
+//!
+//! let patch: ListPatch<i32> = serde_json::from_str("[7, 8]").unwrap();
+//! assert_eq!(patch, ListPatch::Replace(vec![7, 8]));
+//! ```
+
+use std::fmt::Display;
+
+/// One edit to a list. `K` is the key type used by `RemoveKey`.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ListOp<T, K = T> {
+    /// Pushes the elements at the end.
+    Append(Vec<T>),
+    /// Inserts the element at the index, shifting later elements; the index may be the length.
+    Insert(usize, T),
+    /// Replaces the element at the index.
+    Set(usize, T),
+    /// Removes the element at the index.
+    Remove(usize),
+    /// Removes every element with this key.
+    RemoveKey(K),
+    /// Replaces the first element with the same key, or appends it if there is none.
+    Upsert(T),
+}
+
+/// A change to a list: either a replacement or a sequence of operations applied in order.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ListPatch<T, K = T> {
+    Replace(Vec<T>),
+    Ops(Vec<ListOp<T, K>>),
+}
+
+impl<T, K> Default for ListPatch<T, K> {
+    /// A patch with no operations, which leaves the list alone.
+    fn default() -> Self {
+        ListPatch::Ops(Vec::new())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ::serde::Serialize, K: ::serde::Serialize> ::serde::Serialize for ListPatch<T, K> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ::serde::ser::SerializeStruct;
+
+        match self {
+            ListPatch::Replace(items) => items.serialize(serializer),
+            ListPatch::Ops(ops) => {
+                let mut patch = serializer.serialize_struct("ListPatch", 1)?;
+                patch.serialize_field("ops", ops)?;
+                patch.end()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ::serde::Deserialize<'de>, K: ::serde::Deserialize<'de>> ::serde::Deserialize<'de> for ListPatch<T, K> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(::serde::Deserialize)]
+        #[serde(untagged, expecting = "an array or an object with an `ops` array")]
+        enum Repr<T, K> {
+            Replace(Vec<T>),
+            Ops { ops: Vec<ListOp<T, K>> },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Replace(items) => ListPatch::Replace(items),
+            Repr::Ops { ops } => ListPatch::Ops(ops),
+        })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ListError {
+    /// A positional operation pointed past the end of the list.
+    OutOfBounds { index: usize, len: usize },
+}
+
+impl Display for ListError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListError::OutOfBounds { index, len } => {
+                write!(f, "index {} is out of bounds for a list of length {}", index, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ListError {}
+
+impl<T, K: PartialEq> ListPatch<T, K> {
+    /// Applies the patch, using `key` to find the elements targeted by `RemoveKey` and
+    /// `Upsert`. On error, the operations before the failing one stay applied.
+    pub fn apply_by<F>(self, list: &mut Vec<T>, mut key: F) -> Result<(), ListError>
+    where
+        F: FnMut(&T) -> K,
+    {
+        let ops = match self {
+            ListPatch::Replace(items) => {
+                *list = items;
+                return Ok(());
+            }
+            ListPatch::Ops(ops) => ops,
+        };
+
+        for op in ops {
+            match op {
+                ListOp::Append(items) => list.extend(items),
+                ListOp::Insert(index, item) => {
+                    check(index, list.len() + 1, list.len())?;
+                    list.insert(index, item);
+                }
+                ListOp::Set(index, item) => {
+                    check(index, list.len(), list.len())?;
+                    list[index] = item;
+                }
+                ListOp::Remove(index) => {
+                    check(index, list.len(), list.len())?;
+                    list.remove(index);
+                }
+                ListOp::RemoveKey(target) => list.retain(|item| key(item) != target),
+                ListOp::Upsert(item) => {
+                    let target = key(&item);
+
+                    match list.iter().position(|existing| key(existing) == target) {
+                        Some(index) => list[index] = item,
+                        None => list.push(item),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes a patch turning `old` into `new`, matching elements by `key`: keys that
+    /// disappeared become `RemoveKey` and new or changed elements become `Upsert`. When
+    /// those operations would not reproduce `new` exactly, for example because elements were
+    /// reordered, the result is a `Replace`.
+    pub fn diff_by<F>(old: &[T], new: &[T], mut key: F) -> Self
+    where
+        T: Clone + PartialEq,
+        K: Clone,
+        F: FnMut(&T) -> K,
+    {
+        if old == new {
+            return ListPatch::Ops(Vec::new());
+        }
+
+        let new_keys: Vec<K> = new.iter().map(&mut key).collect();
+        let old_keys: Vec<K> = old.iter().map(&mut key).collect();
+        let mut ops = Vec::new();
+
+        for (index, old_key) in old_keys.iter().enumerate() {
+            if !new_keys.contains(old_key) && !old_keys[..index].contains(old_key) {
+                ops.push(ListOp::RemoveKey(old_key.clone()));
+            }
+        }
+
+        for item in new {
+            if !old.contains(item) {
+                ops.push(ListOp::Upsert(item.clone()));
+            }
+        }
+
+        let mut patched = old.to_vec();
+        let patch = ListPatch::Ops(ops);
+
+        match patch.clone().apply_by(&mut patched, &mut key) {
+            Ok(()) if patched == new => patch,
+            _ => ListPatch::Replace(new.to_vec()),
+        }
+    }
+}
+
+impl<T: PartialEq> ListPatch<T> {
+    /// Applies the patch, using the elements themselves as keys.
+    pub fn apply(self, list: &mut Vec<T>) -> Result<(), ListError>
+    where
+        T: Clone,
+    {
+        self.apply_by(list, T::clone)
+    }
+
+    /// Computes a patch turning `old` into `new`, using the elements themselves as keys.
+    pub fn diff(old: &[T], new: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        ListPatch::diff_by(old, new, T::clone)
+    }
+}
+
+fn check(index: usize, bound: usize, len: usize) -> Result<(), ListError> {
+    if index < bound {
+        Ok(())
+    } else {
+        Err(ListError::OutOfBounds { index, len })
+    }
+}
@@ -0,0 +1,137 @@
+//! JSON Merge Patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)).
+//!
+//! A merge patch is a JSON document in which a missing member leaves the target alone,
+//! `null` removes the member and any other value replaces it, recursing into objects.
+//! That is exactly `Undefined`, `Null` and `Value`, so a struct of `Null` fields that skips
+//! `Undefined` when serialized (such as one generated by `#[derive(Patch)]`) is a typed
+//! merge patch.
+//!
+//! ```
+//! use nulls::merge_patch;
+//! use serde_json::json;
+//!
+//! let mut target = json!({ "title": "Goodbye!", "author": { "givenName": "John", "familyName": "Doe" } });
+//! let patch = json!({ "title": "Hello!", "author": { "familyName": null } });
+//!
+//! merge_patch::apply(&mut target, &patch);
+//! assert_eq!(target, json!({ "title": "Hello!", "author": { "givenName": "John" } }));
+//!
+//! let old = json!({ "a": 1, "b": { "c": 2, "d": 3 } });
+//! let new = json!({ "a": 1, "b": { "c": 4 }, "e": 5 });
+//! assert_eq!(merge_patch::diff(&old, &new), json!({ "b": { "c": 4, "d": null }, "e": 5 }));
+//! ```
+//!
+//! The typed versions go through `serde_json::Value`:
+//!
+//! ```
+//! use nulls::{merge_patch, Null};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct User {
+//!     name: String,
+//!     bio: Option<String>,
+//! }
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct UserPatch {
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     name: Null<String>,
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     bio: Null<String>,
+//! }
+//!
+//! let mut user = User { name: "nulls".into(), bio: Some("hi".into()) };
+//! let patch = UserPatch { name: Null::Undefined, bio: Null::Null };
+//!
+//! merge_patch::apply_typed(&mut user, &patch).unwrap();
+//! assert_eq!(user, User { name: "nulls".into(), bio: None });
+//!
+//! let new = User { name: "null".into(), bio: None };
+//! let patch: UserPatch = merge_patch::diff_typed(&user, &new).unwrap();
+//! assert_eq!(patch, UserPatch { name: nulls::new("null".into()), bio: Null::Undefined });
+//! ```
+
+use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Applies `patch` to `target` following the algorithm in section 2 of RFC 7386.
+pub fn apply(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    let Value::Object(target) = target else {
+        unreachable!()
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            apply(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Computes a merge patch that turns `old` into `new`.
+///
+/// Merge patches cannot set a member to `null`, because `null` means "remove", so a
+/// member that is `null` in `new` is removed rather than kept. Arrays are replaced
+/// as a whole.
+pub fn diff(old: &Value, new: &Value) -> Value {
+    let (Value::Object(old), Value::Object(new)) = (old, new) else {
+        return new.clone();
+    };
+
+    let mut patch = Map::new();
+
+    for key in old.keys() {
+        if !new.contains_key(key) {
+            patch.insert(key.clone(), Value::Null);
+        }
+    }
+
+    for (key, value) in new {
+        match old.get(key) {
+            Some(old) if old == value => {}
+            Some(old @ Value::Object(_)) if value.is_object() => {
+                patch.insert(key.clone(), diff(old, value));
+            }
+            _ => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    Value::Object(patch)
+}
+
+/// Applies a typed merge patch, such as a struct of `Null` fields that skips `Undefined`,
+/// to `target`. `target` is left unchanged if an error is returned.
+pub fn apply_typed<T, P>(target: &mut T, patch: &P) -> Result<(), serde_json::Error>
+where
+    T: Serialize + DeserializeOwned,
+    P: Serialize + ?Sized,
+{
+    let mut value = serde_json::to_value(&*target)?;
+    apply(&mut value, &serde_json::to_value(patch)?);
+    *target = serde_json::from_value(value)?;
+    Ok(())
+}
+
+/// Computes the merge patch turning `old` into `new` and deserializes it into `P`, which
+/// is usually a struct of `Null` fields: unchanged fields come back `Undefined`.
+pub fn diff_typed<T, P>(old: &T, new: &T) -> Result<P, serde_json::Error>
+where
+    T: Serialize + ?Sized,
+    P: DeserializeOwned,
+{
+    serde_json::from_value(diff(&serde_json::to_value(old)?, &serde_json::to_value(new)?))
+}
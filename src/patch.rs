@@ -0,0 +1,242 @@
+//! Applying patch structs, usually generated by `#[derive(Patch)]`, onto the values they
+//! describe.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
+
+use crate::Null;
+
+/// A type that can be updated in place from a patch.
+///
+/// `#[derive(ApplyPatch)]` implements this with [`Null::update_to`](crate::Null::update_to)
+/// semantics for every field: `Value` overwrites, `Null` clears an `Option` field and
+/// `Undefined` leaves the field alone.
+pub trait ApplyPatch {
+    type Patch;
+
+    /// Applies `patch` onto `self`. When an error is returned, `self` is left unchanged.
+    fn apply(&mut self, patch: Self::Patch) -> Result<(), PatchError>;
+
+    /// Checks whether `apply` would accept `patch`, without applying it. Implementations
+    /// that can reject a patch should override this so that nested patches are checked
+    /// before their parent writes anything.
+    fn validate(patch: &Self::Patch) -> Result<(), PatchError> {
+        let _ = patch;
+        Ok(())
+    }
+}
+
+/// A type that can apply a patch and return the patch that undoes it, usually implemented by
+/// `#[derive(ApplyPatch)]` with `#[null(inverse)]`.
+pub trait ApplyWithInverse: ApplyPatch {
+    /// Applies `patch` like [`ApplyPatch::apply`] and returns its inverse: the old value of
+    /// every field that changed, with everything else `Undefined`. Applying the inverse
+    /// restores the previous state.
+    fn apply_with_inverse(&mut self, patch: Self::Patch) -> Result<Self::Patch, PatchError>;
+}
+
+/// A type that can describe the change between two of its values as a patch.
+///
+/// `#[derive(Diff)]` implements this field by field: unchanged fields become `Undefined`,
+/// `Option` fields that were cleared become `Null` and anything else that changed becomes
+/// `Value` with a clone of the new value. Applying the result to `old` gives `new`.
+pub trait Diff {
+    type Patch;
+
+    fn diff(old: &Self, new: &Self) -> Self::Patch;
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum PatchError {
+    /// The patch set the named field to `Null`, but the field is not an `Option`.
+    Null(&'static str),
+    /// The named field is required, but the patch left it `Undefined`.
+    Missing(&'static str),
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchError::Null(field) => write!(f, "field `{}` cannot be set to null", field),
+            PatchError::Missing(field) => write!(f, "field `{}` is required", field),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Maps that map patches can be applied to, implemented for `HashMap` and `BTreeMap`.
+pub trait Map<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+
+    fn insert(&mut self, key: K, value: V);
+
+    fn remove(&mut self, key: &K);
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Map<K, V> for HashMap<K, V, S> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn remove(&mut self, key: &K) {
+        HashMap::remove(self, key);
+    }
+}
+
+impl<K: Ord, V> Map<K, V> for BTreeMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        BTreeMap::insert(self, key, value);
+    }
+
+    fn remove(&mut self, key: &K) {
+        BTreeMap::remove(self, key);
+    }
+}
+
+/// Applies a map patch with JSON Merge Patch semantics: `Value` entries are inserted or
+/// replaced, `Null` entries are removed and `Undefined` entries, like keys missing from the
+/// patch, leave the target alone.
+///
+/// With the `derive` feature, `#[null(map)]` on a `HashMap<K, V>` or `BTreeMap<K, V>` field
+/// makes its patch a `Null<HashMap<K, Null<V>>>` that is applied this way.
+///
+/// ```
+/// use nulls::patch::apply_map;
+/// use nulls::Null;
+/// use std::collections::BTreeMap;
+///
+/// let mut labels = BTreeMap::from([("env", "dev"), ("team", "core")]);
+/// apply_map(&mut labels, [("env", nulls::new("prod")), ("team", Null::Null), ("owner", nulls::new("me"))]);
+///
+/// assert_eq!(labels, BTreeMap::from([("env", "prod"), ("owner", "me")]));
+/// ```
+pub fn apply_map<K, V, M, P>(target: &mut M, patch: P)
+where
+    M: Map<K, V>,
+    P: IntoIterator<Item = (K, Null<V>)>,
+{
+    for (key, value) in patch {
+        match value {
+            Null::Value(value) => target.insert(key, value),
+            Null::Null => target.remove(&key),
+            Null::Undefined => {}
+        }
+    }
+}
+
+/// Merges `other` into the map patch `patch`, so that applying the result has the same
+/// effect as applying `patch` and then `other`.
+///
+/// ```
+/// use nulls::patch::merge_map;
+/// use nulls::Null;
+/// use std::collections::HashMap;
+///
+/// let mut patch = HashMap::from([("a", nulls::new(1)), ("b", nulls::new(2))]);
+/// merge_map(&mut patch, [("a", Null::Null), ("b", Null::Undefined), ("c", nulls::new(3))]);
+///
+/// assert_eq!(patch, HashMap::from([("a", Null::Null), ("b", nulls::new(2)), ("c", nulls::new(3))]));
+/// ```
+pub fn merge_map<K, V, M, P>(patch: &mut M, other: P)
+where
+    M: Map<K, Null<V>>,
+    P: IntoIterator<Item = (K, Null<V>)>,
+{
+    for (key, value) in other {
+        if !value.is_undefined() {
+            patch.insert(key, value);
+        }
+    }
+}
+
+/// Computes the map patch turning `old` into `new`: removed keys become `Null`, added or
+/// changed keys become `Value` and unchanged keys are left out.
+///
+/// ```
+/// use nulls::patch::diff_map;
+/// use nulls::Null;
+/// use std::collections::BTreeMap;
+///
+/// let old = BTreeMap::from([("a", 1), ("b", 2)]);
+/// let new = BTreeMap::from([("b", 3), ("c", 4)]);
+///
+/// let patch: BTreeMap<_, _> = diff_map(&old, &new);
+/// assert_eq!(patch, BTreeMap::from([("a", Null::Null), ("b", nulls::new(3)), ("c", nulls::new(4))]));
+/// ```
+pub fn diff_map<'a, K, V, M, P>(old: &'a M, new: &'a M) -> P
+where
+    M: Map<K, V>,
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Clone + 'a,
+    V: Clone + PartialEq + 'a,
+    P: FromIterator<(K, Null<V>)>,
+{
+    let removed = old
+        .into_iter()
+        .filter(|&(key, _)| new.get(key).is_none())
+        .map(|(key, _)| (key.clone(), Null::Null));
+
+    let changed = new
+        .into_iter()
+        .filter(|&(key, value)| old.get(key) != Some(value))
+        .map(|(key, value)| (key.clone(), Null::Value(value.clone())));
+
+    removed.chain(changed).collect()
+}
+
+/// Builds a patch struct literal. Each value is wrapped in `Value` after going through
+/// `Into`, `null` and `undefined` stand for `Null` and `Undefined`, and fields that are not
+/// listed are filled from `Default`, which for a generated patch means `Undefined`.
+///
+/// Because of the `Into`, integer and float literals need a suffix unless the field is an
+/// `i32` or `f64`.
+///
+/// ```
+/// use nulls::{patch, Null};
+///
+/// #[derive(Default, PartialEq, Debug)]
+/// struct UserPatch {
+///     name: Null<String>,
+///     bio: Null<String>,
+///     age: Null<u32>,
+///     email: Null<String>,
+/// }
+///
+/// let patch = patch!(UserPatch { name: "nulls", bio: null, age: 21u32 });
+/// assert_eq!(
+///     patch,
+///     UserPatch { name: nulls::new("nulls".into()), bio: Null::Null, age: nulls::new(21), email: Null::Undefined },
+/// );
+///
+/// assert_eq!(patch!(UserPatch { email: undefined }), UserPatch::default());
+/// ```
+#[macro_export]
+macro_rules! patch {
+    ($($ty:ident)::+ { $($fields:tt)* }) => {
+        $crate::patch!(@build [$($ty)::+] [] $($fields)*)
+    };
+    (@build [$($ty:tt)*] [$($done:tt)*]) => {
+        $($ty)* { $($done)* ..::core::default::Default::default() }
+    };
+    (@build $ty:tt [$($done:tt)*] $field:ident : null $(, $($rest:tt)*)?) => {
+        $crate::patch!(@build $ty [$($done)* $field: $crate::Null::Null,] $($($rest)*)?)
+    };
+    (@build $ty:tt [$($done:tt)*] $field:ident : undefined $(, $($rest:tt)*)?) => {
+        $crate::patch!(@build $ty [$($done)* $field: $crate::Null::Undefined,] $($($rest)*)?)
+    };
+    (@build $ty:tt [$($done:tt)*] $field:ident : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::patch!(
+            @build $ty [$($done)* $field: $crate::Null::Value(::core::convert::Into::into($value)),] $($($rest)*)?
+        )
+    };
+}
@@ -0,0 +1,196 @@
+//! Building patches from dotted field paths.
+//!
+//! [`PatchOps`] collects operations such as `set("profile.name", value)` and
+//! `clear("profile.bio")`, then materializes them into a typed patch struct through its
+//! [`Reflect`] implementation. With the `derive` feature, `#[derive(Patch)]` implements
+//! [`Reflect`] for the generated patch, following `#[null(nested)]` fields for paths with
+//! more than one segment and using the `#[null(rename)]` names. This is meant for admin
+//! tools and rule engines that only know which fields to touch at runtime.
+//!
+//! Paths are segments separated by `.`, matched against the serialized field names.
+//! Values go through `serde_json`, so anything `Serialize` can be set as long as it
+//! deserializes into the field's type.
+
+use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+use serde_json::Value;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Null;
+
+/// A dotted path to a field, such as `profile.name`.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub struct FieldPath {
+    segments: Vec<String>,
+}
+
+impl FieldPath {
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+}
+
+impl From<&str> for FieldPath {
+    fn from(path: &str) -> Self {
+        FieldPath {
+            segments: path.split('.').map(str::to_owned).collect(),
+        }
+    }
+}
+
+impl From<String> for FieldPath {
+    fn from(path: String) -> Self {
+        FieldPath::from(path.as_str())
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = std::convert::Infallible;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Ok(FieldPath::from(path))
+    }
+}
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.segments.join("."))
+    }
+}
+
+/// An operation on one field of a patch.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PatchOp {
+    /// Sets the field to `Value`.
+    Set(FieldPath, Value),
+    /// Sets the field to `Null`.
+    Clear(FieldPath),
+}
+
+/// A list of [`PatchOp`]s, applied in order, so a later operation on the same path wins.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PatchOps {
+    ops: Vec<PatchOp>,
+}
+
+impl PatchOps {
+    pub fn new() -> Self {
+        PatchOps::default()
+    }
+
+    /// Adds an operation setting the field at `path` to `value`.
+    pub fn set<T>(&mut self, path: impl Into<FieldPath>, value: T) -> Result<&mut Self, PathError>
+    where
+        T: Serialize,
+    {
+        let value = serde_json::to_value(value).map_err(PathError::Json)?;
+        self.ops.push(PatchOp::Set(path.into(), value));
+        Ok(self)
+    }
+
+    /// Adds an operation setting the field at `path` to `Null`.
+    pub fn clear(&mut self, path: impl Into<FieldPath>) -> &mut Self {
+        self.ops.push(PatchOp::Clear(path.into()));
+        self
+    }
+
+    pub fn push(&mut self, op: PatchOp) -> &mut Self {
+        self.ops.push(op);
+        self
+    }
+
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    /// Applies the operations onto an existing patch.
+    pub fn apply_to<P: Reflect>(&self, patch: &mut P) -> Result<(), PathError> {
+        for op in &self.ops {
+            match op {
+                PatchOp::Set(path, value) => patch.set_path(path.segments(), Null::Value(value.clone()))?,
+                PatchOp::Clear(path) => patch.set_path(path.segments(), Null::Null)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds a patch from the operations; fields without an operation stay `Undefined`.
+    pub fn build<P: Reflect>(&self) -> Result<P, PathError> {
+        let mut patch = P::default();
+        self.apply_to(&mut patch)?;
+        Ok(patch)
+    }
+}
+
+/// Describes a patch field in a [`Reflect`] table.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct FieldInfo {
+    /// The serialized name of the field.
+    pub name: &'static str,
+    /// Whether the field accepts `Null`.
+    pub nullable: bool,
+    /// Whether the field holds a nested patch that longer paths continue into.
+    pub nested: bool,
+}
+
+/// A patch struct whose fields can be set by path, usually implemented by `#[derive(Patch)]`.
+pub trait Reflect: Default {
+    /// The fields of the patch, in declaration order.
+    const FIELDS: &'static [FieldInfo];
+
+    /// Sets the field at `path`, relative to this patch, to `value`. A `Value` must
+    /// deserialize into the field's type.
+    fn set_path(&mut self, path: &[String], value: Null<Value>) -> Result<(), PathError>;
+}
+
+#[derive(Debug)]
+pub enum PathError {
+    /// No field matches the path.
+    UnknownField(String),
+    /// The path was cleared, but the field does not accept `Null`.
+    NotNullable(String),
+    /// A value could not be converted to or from JSON.
+    Json(serde_json::Error),
+}
+
+impl PathError {
+    /// Prepends `segment` to the path reported by the error, for nested patches.
+    #[doc(hidden)]
+    pub fn __prefixed(self, segment: &str) -> Self {
+        match self {
+            PathError::UnknownField(path) => PathError::UnknownField(format!("{}.{}", segment, path)),
+            PathError::NotNullable(path) => PathError::NotNullable(format!("{}.{}", segment, path)),
+            error => error,
+        }
+    }
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::UnknownField(path) => write!(f, "no field at `{}`", path),
+            PathError::NotNullable(path) => write!(f, "field `{}` cannot be set to null", path),
+            PathError::Json(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PathError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn __from_value<T: DeserializeOwned>(value: Null<Value>) -> Result<Null<T>, PathError> {
+    Ok(match value {
+        Null::Value(value) => Null::Value(serde_json::from_value(value).map_err(PathError::Json)?),
+        Null::Null => Null::Null,
+        Null::Undefined => Null::Undefined,
+    })
+}
@@ -0,0 +1,569 @@
+//! Postgres SQL builders for partial updates.
+//!
+//! Table and column names are written into the statement as given and are not
+//! escaped; only values are bound as parameters.
+
+use ::sqlx::encode::IsNull;
+use ::sqlx::error::BoxDynError;
+use ::sqlx::postgres::{PgArgumentBuffer, PgArguments};
+use ::sqlx::query::Query;
+use ::sqlx::query_builder::Separated;
+use ::sqlx::{Arguments, Encode, Postgres, QueryBuilder, Type};
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use crate::Null;
+
+#[derive(Debug)]
+pub enum BuildError {
+    /// There were no rows, or every field was `Undefined`, so there is nothing to write.
+    Empty,
+    /// A bound value failed to encode.
+    Encode(BoxDynError),
+    /// A column was given a value twice in the same row.
+    Duplicate(String),
+    /// `build` was already called; its query took the bound arguments.
+    Built,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Empty => write!(f, "no columns to write"),
+            BuildError::Encode(error) => write!(f, "failed to encode a bound value: {}", error),
+            BuildError::Duplicate(column) => write!(f, "column `{}` was set twice in the same row", column),
+            BuildError::Built => write!(f, "the query was already built"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Empty | BuildError::Duplicate(_) | BuildError::Built => None,
+            BuildError::Encode(error) => Some(error.as_ref()),
+        }
+    }
+}
+
+/// Builds an `UPDATE` that only assigns the columns whose value is not `Undefined`.
+///
+/// ```
+/// use nulls::pg::UpdateBuilder;
+/// use nulls::Null;
+/// use sqlx::Execute;
+///
+/// let mut update = UpdateBuilder::new("users");
+/// update
+///     .set("name", nulls::new("nulls"))
+///     .set("bio", Null::<String>::Null)
+///     .set("email", Null::<String>::Undefined)
+///     .filter("id", 7)
+///     .returning("id");
+///
+/// let query = update.build().unwrap();
+/// assert_eq!(query.sql(), "UPDATE users SET name = $1, bio = $2 WHERE id = $3 RETURNING id");
+/// ```
+pub struct UpdateBuilder<'q> {
+    table: String,
+    assignments: Vec<String>,
+    filters: Vec<String>,
+    returning: Option<String>,
+    arguments: PgArguments,
+    error: Option<BoxDynError>,
+    built: bool,
+    sql: String,
+    marker: PhantomData<&'q ()>,
+}
+
+impl<'q> UpdateBuilder<'q> {
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            assignments: Vec::new(),
+            filters: Vec::new(),
+            returning: None,
+            arguments: PgArguments::default(),
+            error: None,
+            built: false,
+            sql: String::new(),
+            marker: PhantomData,
+        }
+    }
+
+    fn bind<T>(&mut self, value: T) -> usize
+    where
+        T: 'q + Encode<'q, Postgres> + Type<Postgres>,
+    {
+        if let Err(error) = self.arguments.add(value) {
+            self.error.get_or_insert(error);
+        }
+
+        self.arguments.len()
+    }
+
+    /// Appends `column = $n` for `Value` and `Null`; `Undefined` is skipped.
+    pub fn set<T>(&mut self, column: &str, value: Null<T>) -> &mut Self
+    where
+        T: 'q + Encode<'q, Postgres> + Type<Postgres>,
+    {
+        if !value.is_undefined() {
+            let index = self.bind(value);
+            self.assignments.push(format!("{} = ${}", column, index));
+        }
+
+        self
+    }
+
+    /// Adds a `column = $n` condition to the `WHERE` clause, joined with `AND`.
+    pub fn filter<T>(&mut self, column: &str, value: T) -> &mut Self
+    where
+        T: 'q + Encode<'q, Postgres> + Type<Postgres>,
+    {
+        let index = self.bind(value);
+        self.filters.push(format!("{} = ${}", column, index));
+        self
+    }
+
+    pub fn returning(&mut self, columns: &str) -> &mut Self {
+        self.returning = Some(columns.to_string());
+        self
+    }
+
+    /// Returns `true` if no column would be assigned.
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// Produces the query, taking the bound arguments out of the builder. It can only be
+    /// called once; later calls fail with [`BuildError::Built`].
+    pub fn build(&mut self) -> Result<Query<'_, Postgres, PgArguments>, BuildError> {
+        if std::mem::replace(&mut self.built, true) {
+            return Err(BuildError::Built);
+        }
+
+        if let Some(error) = self.error.take() {
+            return Err(BuildError::Encode(error));
+        }
+
+        if self.assignments.is_empty() {
+            return Err(BuildError::Empty);
+        }
+
+        self.sql = format!("UPDATE {} SET {}", self.table, self.assignments.join(", "));
+
+        if !self.filters.is_empty() {
+            self.sql.push_str(" WHERE ");
+            self.sql.push_str(&self.filters.join(" AND "));
+        }
+
+        if let Some(returning) = &self.returning {
+            self.sql.push_str(" RETURNING ");
+            self.sql.push_str(returning);
+        }
+
+        Ok(::sqlx::query_with(&self.sql, std::mem::take(&mut self.arguments)))
+    }
+}
+
+/// Builds a multi-row `INSERT` where `Undefined` writes `DEFAULT` and `Null` binds `NULL`.
+///
+/// The column list is the union of the columns given in any row, in the order they
+/// first appear; a row that leaves a column out gets `DEFAULT` for it too.
+///
+/// ```
+/// use nulls::pg::InsertBuilder;
+/// use nulls::Null;
+/// use sqlx::Execute;
+///
+/// let mut insert = InsertBuilder::new("users");
+/// insert.row()
+///     .value("name", nulls::new("nulls"))
+///     .value("bio", Null::<String>::Undefined);
+/// insert.row()
+///     .value("name", nulls::new("enigs"))
+///     .value("bio", Null::<String>::Null)
+///     .value("email", nulls::new("enigs@example.com"));
+/// insert.returning("id");
+///
+/// let query = insert.build().unwrap();
+/// assert_eq!(
+///     query.sql(),
+///     "INSERT INTO users (name, bio, email) VALUES ($1, DEFAULT, DEFAULT), ($2, $3, $4) RETURNING id",
+/// );
+/// ```
+pub struct InsertBuilder<'q> {
+    table: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<(usize, usize)>>,
+    conflict: Option<Conflict>,
+    returning: Option<String>,
+    arguments: PgArguments,
+    error: Option<BuildError>,
+    built: bool,
+    sql: String,
+    marker: PhantomData<&'q ()>,
+}
+
+/// The conflict target of an upsert.
+enum Conflict {
+    Columns(Vec<String>),
+    Constraint(String),
+}
+
+impl<'q> InsertBuilder<'q> {
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            columns: Vec::new(),
+            rows: Vec::new(),
+            conflict: None,
+            returning: None,
+            arguments: PgArguments::default(),
+            error: None,
+            built: false,
+            sql: String::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Starts a new row; following [`value`](Self::value) calls fill it in.
+    pub fn row(&mut self) -> &mut Self {
+        self.rows.push(Vec::new());
+        self
+    }
+
+    /// Sets `column` in the current row, binding `Value` and `Null` and leaving
+    /// `Undefined` as `DEFAULT`. Starts the first row if none was started yet.
+    ///
+    /// Binding a column twice in the same row makes [`build`](Self::build) fail with
+    /// [`BuildError::Duplicate`].
+    pub fn value<T>(&mut self, column: &str, value: Null<T>) -> &mut Self
+    where
+        T: 'q + Encode<'q, Postgres> + Type<Postgres>,
+    {
+        let position = match self.columns.iter().position(|name| name == column) {
+            Some(position) => position,
+            None => {
+                self.columns.push(column.to_string());
+                self.columns.len() - 1
+            }
+        };
+
+        if self.rows.is_empty() {
+            self.rows.push(Vec::new());
+        }
+
+        if !value.is_undefined() {
+            if self.rows.last().unwrap().iter().any(|(column, _)| *column == position) {
+                self.error.get_or_insert(BuildError::Duplicate(column.to_string()));
+                return self;
+            }
+
+            if let Err(error) = self.arguments.add(value) {
+                self.error.get_or_insert(BuildError::Encode(error));
+            }
+
+            let index = self.arguments.len();
+            self.rows.last_mut().unwrap().push((position, index));
+        }
+
+        self
+    }
+
+    /// Turns the insert into an upsert on a unique index over `columns`, written as
+    /// `ON CONFLICT (columns)`. Each entry is one column, so quoted identifiers may
+    /// contain commas.
+    ///
+    /// On conflict only the columns that are not `Undefined` in every row are assigned,
+    /// each from `EXCLUDED`, so a column no row provided keeps its stored value. The
+    /// target columns are never assigned. When no column is left the statement uses
+    /// `DO NOTHING`.
+    ///
+    /// ```
+    /// use nulls::pg::InsertBuilder;
+    /// use nulls::Null;
+    /// use sqlx::Execute;
+    ///
+    /// let mut upsert = InsertBuilder::new("users");
+    /// upsert.row()
+    ///     .value("id", nulls::new(7))
+    ///     .value("name", nulls::new("nulls"))
+    ///     .value("bio", Null::<String>::Null)
+    ///     .value("email", Null::<String>::Undefined);
+    /// upsert.on_conflict(&["id"]);
+    ///
+    /// let query = upsert.build().unwrap();
+    /// assert_eq!(
+    ///     query.sql(),
+    ///     "INSERT INTO users (id, name, bio, email) VALUES ($1, $2, $3, DEFAULT) \
+    ///      ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name, bio = EXCLUDED.bio",
+    /// );
+    /// ```
+    pub fn on_conflict(&mut self, columns: &[&str]) -> &mut Self {
+        self.conflict = Some(Conflict::Columns(columns.iter().map(|column| column.to_string()).collect()));
+        self
+    }
+
+    /// Like [`on_conflict`](Self::on_conflict), but on a named constraint, written as
+    /// `ON CONFLICT ON CONSTRAINT name`. Its columns are not known here, so every column
+    /// defined in every row is assigned, including the ones the constraint covers.
+    pub fn on_constraint(&mut self, name: &str) -> &mut Self {
+        self.conflict = Some(Conflict::Constraint(name.to_string()));
+        self
+    }
+
+    pub fn returning(&mut self, columns: &str) -> &mut Self {
+        self.returning = Some(columns.to_string());
+        self
+    }
+
+    /// Returns `true` if no row was started.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Produces the query, taking the bound arguments out of the builder. It can only be
+    /// called once; later calls fail with [`BuildError::Built`].
+    ///
+    /// A single row without any columns becomes `INSERT INTO table DEFAULT VALUES`;
+    /// several such rows cannot be expressed and fail with [`BuildError::Empty`].
+    pub fn build(&mut self) -> Result<Query<'_, Postgres, PgArguments>, BuildError> {
+        if std::mem::replace(&mut self.built, true) {
+            return Err(BuildError::Built);
+        }
+
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        if self.rows.is_empty() || (self.columns.is_empty() && self.rows.len() > 1) {
+            return Err(BuildError::Empty);
+        }
+
+        if self.columns.is_empty() {
+            self.sql = format!("INSERT INTO {} DEFAULT VALUES", self.table);
+        } else {
+            let rows = self.rows.iter()
+                .map(|row| {
+                    let values = (0..self.columns.len())
+                        .map(|column| match row.iter().find(|(position, _)| *position == column) {
+                            Some((_, index)) => format!("${}", index),
+                            None => "DEFAULT".to_string(),
+                        })
+                        .collect::<Vec<_>>();
+
+                    format!("({})", values.join(", "))
+                })
+                .collect::<Vec<_>>();
+
+            self.sql = format!("INSERT INTO {} ({}) VALUES {}", self.table, self.columns.join(", "), rows.join(", "));
+        }
+
+        if let Some(conflict) = &self.conflict {
+            let (target, keys) = match conflict {
+                Conflict::Columns(columns) => (format!("({})", columns.join(", ")), columns.as_slice()),
+                Conflict::Constraint(name) => (format!("ON CONSTRAINT {}", name), [].as_slice()),
+            };
+
+            let assignments = self.columns.iter()
+                .enumerate()
+                .filter(|(_, name)| !keys.contains(name))
+                .filter(|(column, _)| self.rows.iter().all(|row| row.iter().any(|(position, _)| position == column)))
+                .map(|(_, name)| format!("{} = EXCLUDED.{}", name, name))
+                .collect::<Vec<_>>();
+
+            if assignments.is_empty() {
+                self.sql.push_str(&format!(" ON CONFLICT {} DO NOTHING", target));
+            } else {
+                self.sql.push_str(&format!(" ON CONFLICT {} DO UPDATE SET {}", target, assignments.join(", ")));
+            }
+        }
+
+        if let Some(returning) = &self.returning {
+            self.sql.push_str(" RETURNING ");
+            self.sql.push_str(returning);
+        }
+
+        Ok(::sqlx::query_with(&self.sql, std::mem::take(&mut self.arguments)))
+    }
+}
+
+/// Writes rows in the `COPY ... FROM STDIN (FORMAT binary)` format, encoding `Null` and
+/// `Undefined` as NULL fields and delegating to `T` for values.
+///
+/// Values are encoded without a connection, so types whose OID is only known by name
+/// (enums, composites, domains and arrays of them) cannot be written; [`field`](Self::field)
+/// rejects such values. A tuple holds at most `i16::MAX` fields.
+///
+/// ```
+/// use nulls::pg::CopyBinaryWriter;
+/// use nulls::Null;
+///
+/// let mut copy = CopyBinaryWriter::new();
+/// copy.row().field(nulls::new(7_i32)).unwrap().field(Null::<String>::Null).unwrap();
+/// let data = copy.finish();
+///
+/// assert!(data.starts_with(b"PGCOPY\n\xff\r\n\0"));
+/// assert!(data.ends_with(&[0xff, 0xff]));
+/// ```
+///
+/// The bytes can then be sent with `PgConnection::copy_in_raw`:
+///
+/// ```no_run
+/// # async fn load(conn: &mut sqlx::PgConnection, data: Vec<u8>) -> Result<(), sqlx::Error> {
+/// let mut copy = conn.copy_in_raw("COPY users (id, bio) FROM STDIN (FORMAT binary)").await?;
+/// copy.send(data).await?;
+/// copy.finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CopyBinaryWriter {
+    buffer: Vec<u8>,
+    row: Option<usize>,
+}
+
+impl CopyBinaryWriter {
+    pub fn new() -> Self {
+        let mut buffer = b"PGCOPY\n\xff\r\n\0".to_vec();
+        // Flags and header extension length.
+        buffer.extend_from_slice(&0_i32.to_be_bytes());
+        buffer.extend_from_slice(&0_i32.to_be_bytes());
+
+        Self { buffer, row: None }
+    }
+
+    /// Starts a new tuple; following [`field`](Self::field) calls fill it in.
+    pub fn row(&mut self) -> &mut Self {
+        self.row = Some(self.buffer.len());
+        self.buffer.extend_from_slice(&0_i16.to_be_bytes());
+        self
+    }
+
+    /// Appends a field to the current tuple, starting one if none was started yet.
+    ///
+    /// Fails without writing anything if the value's type has no known OID or the tuple
+    /// is full.
+    pub fn field<'q, T>(&mut self, value: Null<T>) -> Result<&mut Self, BoxDynError>
+    where
+        T: Encode<'q, Postgres> + Type<Postgres>,
+    {
+        let row = match self.row {
+            Some(row) => row,
+            None => self.row().row.unwrap(),
+        };
+
+        let count = i16::from_be_bytes([self.buffer[row], self.buffer[row + 1]])
+            .checked_add(1)
+            .ok_or("a COPY tuple holds at most i16::MAX fields")?;
+
+        match value {
+            Null::Value(value) => {
+                let type_info = value.produces().unwrap_or_else(T::type_info);
+
+                if type_info.oid().is_none() {
+                    return Err(format!("type `{}` has no known OID and cannot be written without a connection", type_info).into());
+                }
+
+                let mut buffer = PgArgumentBuffer::default();
+                match value.encode(&mut buffer)? {
+                    IsNull::Yes => self.buffer.extend_from_slice(&(-1_i32).to_be_bytes()),
+                    IsNull::No => {
+                        self.buffer.extend_from_slice(&i32::try_from(buffer.len())?.to_be_bytes());
+                        self.buffer.extend_from_slice(&buffer);
+                    }
+                }
+            }
+            Null::Null | Null::Undefined => self.buffer.extend_from_slice(&(-1_i32).to_be_bytes()),
+        }
+
+        self.buffer[row..row + 2].copy_from_slice(&count.to_be_bytes());
+
+        Ok(self)
+    }
+
+    /// Appends the trailer and returns the data to send.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buffer.extend_from_slice(&(-1_i16).to_be_bytes());
+        self.buffer
+    }
+}
+
+impl Default for CopyBinaryWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What [`QueryBuilderExt::push_null_bind`] writes for `Undefined`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum UndefinedBind {
+    /// Write nothing.
+    #[default]
+    Skip,
+    /// Bind `NULL`, the same as for `Null`.
+    Null,
+    /// Write the `DEFAULT` keyword, e.g. inside an `INSERT` value list.
+    Default,
+}
+
+/// `Null`-aware helpers for [`sqlx::QueryBuilder`].
+///
+/// ```
+/// use nulls::pg::{QueryBuilderExt, SeparatedExt, UndefinedBind};
+/// use nulls::Null;
+/// use sqlx::{Postgres, QueryBuilder};
+///
+/// let mut query = QueryBuilder::<Postgres>::new("UPDATE users SET ");
+/// let mut set = query.separated(", ");
+/// set.push_set_clause("name", nulls::new("nulls"));
+/// set.push_set_clause("email", Null::<String>::Undefined);
+/// set.push_set_clause("bio", Null::<String>::Null);
+/// assert_eq!(query.sql(), "UPDATE users SET name = $1, bio = $2");
+///
+/// let mut query = QueryBuilder::<Postgres>::new("INSERT INTO users (name) VALUES (");
+/// query.push_null_bind(Null::<String>::Undefined, UndefinedBind::Default).push(")");
+/// assert_eq!(query.sql(), "INSERT INTO users (name) VALUES (DEFAULT)");
+/// ```
+pub trait QueryBuilderExt<'args> {
+    /// Binds the value for `Value`, `NULL` for `Null`, and follows `policy` for `Undefined`.
+    fn push_null_bind<T>(&mut self, value: Null<T>, policy: UndefinedBind) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres>;
+}
+
+impl<'args> QueryBuilderExt<'args> for QueryBuilder<'args, Postgres> {
+    fn push_null_bind<T>(&mut self, value: Null<T>, policy: UndefinedBind) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres>,
+    {
+        match (value, policy) {
+            (Null::Undefined, UndefinedBind::Skip) => self,
+            (Null::Undefined, UndefinedBind::Default) => self.push("DEFAULT"),
+            (value, _) => self.push_bind(value.undefined_to_null()),
+        }
+    }
+}
+
+/// `Null`-aware helpers for [`sqlx::query_builder::Separated`].
+pub trait SeparatedExt<'args> {
+    /// Pushes `column = $n` for `Value` and `Null`, preceded by the separator when
+    /// needed; `Undefined` writes nothing.
+    fn push_set_clause<T>(&mut self, column: &str, value: Null<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres>;
+}
+
+impl<'args, Sep: Display> SeparatedExt<'args> for Separated<'_, 'args, Postgres, Sep> {
+    fn push_set_clause<T>(&mut self, column: &str, value: Null<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Type<Postgres>,
+    {
+        if value.is_undefined() {
+            return self;
+        }
+
+        self.push(format_args!("{} = ", column)).push_bind_unseparated(value)
+    }
+}
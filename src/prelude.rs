@@ -0,0 +1,18 @@
+//! Glob-importable set of the commonly used items.
+//!
+//! ```
+//! use nulls::prelude::*;
+//!
+//! let name: Null<&str> = new("nulls");
+//! assert_eq!(coalesce!(null(), undefined(), name), new("nulls"));
+//! ```
+
+pub use crate::{coalesce, null_try};
+pub use crate::{new, null, undefined};
+pub use crate::{MergeStrategy, Null, NullError};
+
+#[cfg(feature = "sqlx")]
+pub use crate::{FetchNullExt, NullRowExt};
+
+#[cfg(feature = "sqlx-postgres")]
+pub use crate::pg::{QueryBuilderExt, SeparatedExt};
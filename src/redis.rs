@@ -0,0 +1,111 @@
+//! Redis encoding for `Null`.
+//!
+//! Every state is written as a single argument so that all three survive a round trip:
+//! `Undefined` as `u`, `Null` as `n`, and `Value` as `v` followed by the bytes `T`
+//! writes. A missing key (`nil`) reads back as `Undefined`.
+//!
+//! A `Value` that `T` writes as several arguments or none, such as a `Vec<String>`, is
+//! written as `l` followed by each argument with its length as a big-endian `u64`, and
+//! read back from an array of those arguments.
+//!
+//! ```
+//! use nulls::Null;
+//! use redis::{FromRedisValue, ToRedisArgs, Value};
+//!
+//! assert_eq!(nulls::new(42).to_redis_args(), vec![b"v42".to_vec()]);
+//! assert_eq!(Null::<i32>::Null.to_redis_args(), vec![b"n".to_vec()]);
+//!
+//! let value = Value::BulkString(b"v42".to_vec());
+//! assert_eq!(Null::<i32>::from_redis_value(&value).unwrap(), Null::Value(42));
+//! assert_eq!(Null::<i32>::from_redis_value(&Value::Nil).unwrap(), Null::Undefined);
+//!
+//! let tags = nulls::new(vec!["a", "bc"]);
+//! let bytes = [b"l".as_slice(), &1u64.to_be_bytes(), b"a", &2u64.to_be_bytes(), b"bc"].concat();
+//! assert_eq!(tags.to_redis_args(), vec![bytes]);
+//!
+//! let value = Value::BulkString(tags.to_redis_args().remove(0));
+//! assert_eq!(Null::<Vec<String>>::from_redis_value(&value).unwrap(), nulls::new(vec!["a".into(), "bc".into()]));
+//! ```
+
+use ::redis::{ErrorKind, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs, Value};
+
+use crate::Null;
+
+const UNDEFINED: u8 = b'u';
+const NULL: u8 = b'n';
+const VALUE: u8 = b'v';
+const LIST: u8 = b'l';
+
+impl<T: ToRedisArgs> ToRedisArgs for Null<T> {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self {
+            Null::Undefined => out.write_arg(&[UNDEFINED]),
+            Null::Null => out.write_arg(&[NULL]),
+            Null::Value(value) => {
+                let args = value.to_redis_args();
+                let mut buffer = Vec::with_capacity(args.iter().map(|arg| arg.len() + 8).sum::<usize>() + 1);
+
+                if let [arg] = args.as_slice() {
+                    buffer.push(VALUE);
+                    buffer.extend_from_slice(arg);
+                } else {
+                    buffer.push(LIST);
+
+                    for arg in &args {
+                        buffer.extend_from_slice(&(arg.len() as u64).to_be_bytes());
+                        buffer.extend_from_slice(arg);
+                    }
+                }
+
+                out.write_arg(&buffer);
+            }
+        }
+    }
+
+    fn num_of_args(&self) -> usize {
+        1
+    }
+}
+
+impl<T: FromRedisValue> FromRedisValue for Null<T> {
+    fn from_redis_value(value: &Value) -> RedisResult<Self> {
+        let bytes = match value {
+            Value::Nil => return Ok(Null::Undefined),
+            Value::BulkString(bytes) => bytes,
+            value => return Err(invalid(value)),
+        };
+
+        match bytes.split_first() {
+            Some((&UNDEFINED, [])) => Ok(Null::Undefined),
+            Some((&NULL, [])) => Ok(Null::Null),
+            Some((&VALUE, rest)) => T::from_owned_redis_value(Value::BulkString(rest.to_vec())).map(Null::Value),
+            Some((&LIST, rest)) => {
+                let args = split_list(rest).ok_or_else(|| invalid(value))?;
+                T::from_owned_redis_value(Value::Array(args)).map(Null::Value)
+            }
+            _ => Err(invalid(value)),
+        }
+    }
+}
+
+/// Splits the length-prefixed arguments of a `LIST` value.
+fn split_list(mut bytes: &[u8]) -> Option<Vec<Value>> {
+    let mut args = Vec::new();
+
+    while let Some((len, rest)) = bytes.split_first_chunk::<8>() {
+        let len = usize::try_from(u64::from_be_bytes(*len)).ok().filter(|&len| len <= rest.len())?;
+
+        let (arg, rest) = rest.split_at(len);
+        args.push(Value::BulkString(arg.to_vec()));
+        bytes = rest;
+    }
+
+    bytes.is_empty().then_some(args)
+}
+
+fn invalid(value: &Value) -> ::redis::RedisError {
+    (ErrorKind::TypeError, "Response was of incompatible type", format!("{:?} is not an encoded Null", value)).into()
+}
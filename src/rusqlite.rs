@@ -0,0 +1,28 @@
+//! rusqlite conversions.
+//!
+//! SQLite has no way to leave a bound parameter unset, so `Undefined` binds as NULL like
+//! `Null`; leave such columns out of the statement to keep their current value. NULL
+//! reads back as `Null`.
+
+use ::rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use ::rusqlite::Result;
+
+use crate::Null;
+
+impl<T: ToSql> ToSql for Null<T> {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        match self {
+            Null::Value(ref value) => value.to_sql(),
+            Null::Undefined | Null::Null => Ok(ToSqlOutput::from(::rusqlite::types::Null)),
+        }
+    }
+}
+
+impl<T: FromSql> FromSql for Null<T> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Null => Ok(Null::Null),
+            value => T::column_result(value).map(Null::Value),
+        }
+    }
+}
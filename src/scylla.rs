@@ -0,0 +1,40 @@
+//! ScyllaDB / Cassandra conversions.
+//!
+//! CQL tells all three states apart on write: `Value` binds the value, `Null` writes a
+//! NULL (a tombstone, like `None`), and `Undefined` binds the protocol's unset value,
+//! which leaves the stored cell as it was. Results never contain unset values, so an
+//! empty cell reads back as `Null`.
+
+use ::scylla::cluster::metadata::ColumnType;
+use ::scylla::deserialize::value::DeserializeValue;
+use ::scylla::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
+use ::scylla::serialize::value::SerializeValue;
+use ::scylla::serialize::writers::{CellWriter, WrittenCellProof};
+use ::scylla::serialize::SerializationError;
+
+use crate::Null;
+
+impl<T: SerializeValue> SerializeValue for Null<T> {
+    fn serialize<'b>(&self, typ: &ColumnType, writer: CellWriter<'b>) -> Result<WrittenCellProof<'b>, SerializationError> {
+        match self {
+            Null::Value(value) => value.serialize(typ, writer),
+            Null::Null => Ok(writer.set_null()),
+            Null::Undefined => Ok(writer.set_unset()),
+        }
+    }
+}
+
+impl<'frame, 'metadata, T> DeserializeValue<'frame, 'metadata> for Null<T>
+    where T: DeserializeValue<'frame, 'metadata>,
+{
+    fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        T::type_check(typ)
+    }
+
+    fn deserialize(typ: &'metadata ColumnType<'metadata>, value: Option<FrameSlice<'frame>>) -> Result<Self, DeserializationError> {
+        match value {
+            Some(_) => T::deserialize(typ, value).map(Null::Value),
+            None => Ok(Null::Null),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! SeaORM conversions.
+//!
+//! `ActiveValue` has a state for "not provided", so all three states survive: `Undefined`
+//! is `NotSet` and is left out of the `INSERT` or `UPDATE`, `Null` is `Set(None)`, and a
+//! value is `Set(Some(..))`.
+
+use ::sea_orm::{ActiveValue, Value};
+
+use crate::Null;
+
+/// `Undefined` becomes `NotSet`, `Null` becomes `Set(None)` and `Value(v)` becomes
+/// `Set(Some(v))`, so a patch field can be assigned straight to an active model.
+impl<T> From<Null<T>> for ActiveValue<Option<T>>
+    where Option<T>: Into<Value>,
+{
+    fn from(value: Null<T>) -> Self {
+        match value {
+            Null::Undefined => ActiveValue::NotSet,
+            value => ActiveValue::Set(value.into_double_option().flatten()),
+        }
+    }
+}
+
+/// `NotSet` becomes `Undefined`; `Set` and `Unchanged` keep their `Option` as `Null`
+/// or `Value`.
+impl<T> From<ActiveValue<Option<T>>> for Null<T>
+    where Option<T>: Into<Value>,
+{
+    fn from(value: ActiveValue<Option<T>>) -> Self {
+        match value {
+            ActiveValue::NotSet => Null::Undefined,
+            ActiveValue::Set(value) | ActiveValue::Unchanged(value) => Null::from_option(value),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! sea-query conversions.
+//!
+//! `Null<T>` converts to the same typed `Value` as `Option<T>`. sea-query has no "not
+//! provided" value, so `Undefined` becomes the typed NULL as well; leave such columns
+//! out of the statement instead.
+
+use ::sea_query::{ArrayType, ColumnType, Nullable, Value, ValueType, ValueTypeErr};
+
+use crate::Null;
+
+impl<T> From<Null<T>> for Value
+    where T: Into<Value> + Nullable,
+{
+    fn from(value: Null<T>) -> Value {
+        value.into_double_option().flatten().into()
+    }
+}
+
+impl<T> ValueType for Null<T>
+    where T: ValueType + Nullable,
+{
+    fn try_from(value: Value) -> Result<Self, ValueTypeErr> {
+        <Option<T> as ValueType>::try_from(value).map(Null::from_option)
+    }
+
+    fn is_option() -> bool {
+        true
+    }
+
+    fn type_name() -> String {
+        format!("Null<{}>", T::type_name())
+    }
+
+    fn array_type() -> ArrayType {
+        T::array_type()
+    }
+
+    fn column_type() -> ColumnType {
+        T::column_type()
+    }
+
+    fn enum_type_name() -> Option<&'static str> {
+        T::enum_type_name()
+    }
+}
@@ -0,0 +1,1161 @@
+//! Serde helpers for `Null` fields.
+//!
+//! `Null` deserializes through `deserialize_option`, so it behaves the same inside
+//! `#[serde(flatten)]` structs, `#[serde(untagged)]` enums and `deny_unknown_fields`
+//! containers as it does in plain structs:
+//!
+//! ```
+//! use nulls::Null;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! struct Profile {
+//!     #[serde(default)]
+//!     bio: Null<String>,
+//! }
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! #[serde(deny_unknown_fields)]
+//! struct User {
+//!     #[serde(default)]
+//!     name: Null<String>,
+//!     #[serde(flatten)]
+//!     profile: Profile,
+//! }
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! #[serde(untagged)]
+//! enum Request {
+//!     Rename {
+//!         #[serde(default)]
+//!         name: Null<String>,
+//!         id: u32,
+//!     },
+//! }
+//!
+//! let user: User = serde_json::from_str(r#"{"bio":null}"#).unwrap();
+//! assert_eq!(user.name, Null::Undefined);
+//! assert_eq!(user.profile.bio, Null::Null);
+//!
+//! let Request::Rename { name, .. } = serde_json::from_str(r#"{"id":1}"#).unwrap();
+//! assert_eq!(name, Null::<String>::Undefined);
+//!
+//! let Request::Rename { name, .. } = serde_json::from_str(r#"{"id":1,"name":null}"#).unwrap();
+//! assert_eq!(name, Null::<String>::Null);
+//! ```
+//!
+//! The deserializer's own error type is passed through untouched, so wrappers such as
+//! `serde_path_to_error` report the exact path of a failing value, including values
+//! nested inside a `Null`:
+//!
+//! ```
+//! use nulls::Null;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct Address {
+//!     #[serde(default)]
+//!     zip: Null<u32>,
+//! }
+//!
+//! #[derive(Deserialize, Debug)]
+//! struct User {
+//!     #[serde(default)]
+//!     address: Null<Address>,
+//! }
+//!
+//! let json = &mut serde_json::Deserializer::from_str(r#"{"address":{"zip":"abc"}}"#);
+//! let err = serde_path_to_error::deserialize::<_, User>(json).unwrap_err();
+//! assert_eq!(err.path().to_string(), "address.zip");
+//! ```
+//!
+//! Other self-describing text formats follow the same rules. In YAML, `key: null` and
+//! `key: ~` read as `Null` and an absent key as `Undefined`. TOML has no null at all;
+//! see [`empty_table`] for a convention that still allows an explicit clear.
+//!
+//! ```
+//! use nulls::Null;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Config {
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     name: Null<String>,
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     port: Null<u16>,
+//!     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+//!     proxy: Null<String>,
+//! }
+//!
+//! let config: Config = serde_yaml::from_str("name: nulls\nproxy: ~\n").unwrap();
+//! assert_eq!(config, Config { name: nulls::new("nulls".into()), port: Null::Undefined, proxy: Null::Null });
+//! assert_eq!(serde_yaml::to_string(&config).unwrap(), "name: nulls\nproxy: null\n");
+//! ```
+
+use ::serde::de::value::{
+    BorrowedBytesDeserializer, BorrowedStrDeserializer, EnumAccessDeserializer, MapAccessDeserializer,
+    SeqAccessDeserializer,
+};
+use ::serde::de::{DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+use ::serde::ser::SerializeTuple;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use crate::{Null, NullError};
+
+mod omit;
+
+pub use omit::OmitUndefined;
+
+#[doc(hidden)]
+pub use ::serde as __serde;
+
+#[doc(hidden)]
+pub use ::serde_json as __serde_json;
+
+/// How `Undefined` is written when a field is not skipped with
+/// `skip_serializing_if = "nulls::serde::is_undefined"`, which `#[derive(Patch)]` emits.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum UndefinedPolicy {
+    /// Emit `Undefined` the same way as `Null` in human-readable formats. Binary formats
+    /// keep the lossless [`compact`] encoding, which writes `Undefined` as its own tag.
+    #[default]
+    Null,
+    /// Fail serialization when an `Undefined` is encountered, in every format.
+    Error,
+    /// Leave `Undefined` struct fields and map entries out of containers serialized
+    /// through [`OmitUndefined`], which applies this policy itself. Any other `Undefined`
+    /// fails serialization.
+    Omit,
+}
+
+thread_local! {
+    static UNDEFINED_POLICY: Cell<UndefinedPolicy> = const { Cell::new(UndefinedPolicy::Null) };
+}
+
+pub fn undefined_policy() -> UndefinedPolicy {
+    UNDEFINED_POLICY.with(Cell::get)
+}
+
+/// Runs `f` with `policy` applied to every `Null` serialized on the current thread.
+///
+/// ```
+/// use nulls::serde::{with_undefined_policy, UndefinedPolicy};
+///
+/// let values = vec![nulls::new(1), nulls::undefined()];
+///
+/// assert_eq!(serde_json::to_string(&values).unwrap(), "[1,null]");
+/// assert!(with_undefined_policy(UndefinedPolicy::Error, || serde_json::to_string(&values)).is_err());
+/// ```
+pub fn with_undefined_policy<R, F: FnOnce() -> R>(policy: UndefinedPolicy, f: F) -> R {
+    struct Reset(UndefinedPolicy);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            UNDEFINED_POLICY.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _reset = Reset(UNDEFINED_POLICY.with(|cell| cell.replace(policy)));
+    f()
+}
+
+/// Serializes `T` with [`UndefinedPolicy::Error`], failing on the first `Undefined`.
+///
+/// ```
+/// use nulls::serde::ErrorOnUndefined;
+/// use nulls::Null;
+///
+/// assert!(serde_json::to_string(&ErrorOnUndefined(vec![Null::<u8>::Undefined])).is_err());
+/// assert_eq!(serde_json::to_string(&ErrorOnUndefined(vec![Null::<u8>::Null])).unwrap(), "[null]");
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub struct ErrorOnUndefined<T>(pub T);
+
+impl<T: Serialize> Serialize for ErrorOnUndefined<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        with_undefined_policy(UndefinedPolicy::Error, || self.0.serialize(serializer))
+    }
+}
+
+/// Human-readable formats get the transparent form (`null` or the value itself), while
+/// binary formats use the lossless [`compact`] encoding. The [`UndefinedPolicy`] is
+/// applied first, so an `Undefined` it rejects fails in either.
+impl<T: Serialize> Serialize for Null<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_undefined() {
+            check_undefined()?;
+        }
+
+        if !serializer.is_human_readable() {
+            return compact::serialize(self, serializer);
+        }
+
+        match self {
+            Null::Value(value) => serializer.serialize_some(value),
+            Null::Null | Null::Undefined => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Fails as the current [`UndefinedPolicy`] requires for an `Undefined`, or returns `Ok`
+/// if it is to be written like `Null`.
+pub(crate) fn check_undefined<E: ::serde::ser::Error>() -> Result<(), E> {
+    match undefined_policy() {
+        UndefinedPolicy::Null => Ok(()),
+        UndefinedPolicy::Error => Err(E::custom(NullError::Undefined)),
+        UndefinedPolicy::Omit => Err(E::custom(omit::NOT_OMITTED)),
+    }
+}
+
+/// Human-readable formats deserialize through `deserialize_option`, so borrowed data
+/// such as `Null<&'de str>` is supported.
+///
+/// A missing field is `Undefined` only with `#[serde(default)]`, which `#[derive(Patch)]`
+/// and `#[nulls::undefined_skip]` emit. Without it, serde reads the missing field
+/// through `deserialize_option` as if it were `null`, in every format, so it becomes
+/// `Null`:
+///
+/// ```
+/// use nulls::Null;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// struct Patch {
+///     #[serde(default)]
+///     name: Null<String>,
+///     bio: Null<String>,
+/// }
+///
+/// let patch: Patch = serde_json::from_str("{}").unwrap();
+/// assert_eq!(patch, Patch { name: Null::Undefined, bio: Null::Null });
+/// ```
+impl<'de, T> Deserialize<'de> for Null<T>
+where T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        NullSeed(PhantomData).deserialize(deserializer)
+    }
+}
+
+/// `DeserializeSeed` for `Null`, applying the inner seed to the `Value` case.
+pub struct NullSeed<S>(pub S);
+
+impl<S> NullSeed<S> {
+    pub fn new(seed: S) -> Self {
+        Self(seed)
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for NullSeed<S>
+where S: DeserializeSeed<'de>,
+{
+    type Value = Null<S::Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_option(self)
+        } else {
+            deserializer.deserialize_tuple(2, compact::CompactVisitor(self.0))
+        }
+    }
+}
+
+/// Builds a `Value` from a primitive handed to the visitor in place of `visit_some`.
+macro_rules! forward_to_seed {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E: ::serde::de::Error>(self, value: $ty) -> Result<Self::Value, E> {
+                self.0.deserialize(value.into_deserializer()).map(Null::Value)
+            }
+        )*
+    };
+}
+
+impl<'de, S> Visitor<'de> for NullSeed<S>
+where S: DeserializeSeed<'de>,
+{
+    type Value = Null<S::Value>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a nullable value")
+    }
+
+    fn visit_none<E: ::serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Null::Null)
+    }
+
+    fn visit_unit<E: ::serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Null::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(deserializer).map(Null::Value)
+    }
+
+    // Deserializers without a notion of options, such as serde's own value
+    // deserializers, forward `deserialize_option` to `deserialize_any`.
+    forward_to_seed! {
+        visit_bool(bool);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+    }
+
+    fn visit_borrowed_str<E: ::serde::de::Error>(self, value: &'de str) -> Result<Self::Value, E> {
+        self.0.deserialize(BorrowedStrDeserializer::new(value)).map(Null::Value)
+    }
+
+    fn visit_borrowed_bytes<E: ::serde::de::Error>(self, value: &'de [u8]) -> Result<Self::Value, E> {
+        self.0.deserialize(BorrowedBytesDeserializer::new(value)).map(Null::Value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.deserialize(SeqAccessDeserializer::new(seq)).map(Null::Value)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.deserialize(MapAccessDeserializer::new(map)).map(Null::Value)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.0.deserialize(EnumAccessDeserializer::new(data)).map(Null::Value)
+    }
+}
+
+/// Path helper for `skip_serializing_if`.
+pub fn is_undefined<T>(value: &Null<T>) -> bool {
+    value.is_undefined()
+}
+
+/// Parses a whole JSON body, treating an empty or whitespace-only body as `Undefined`,
+/// a literal `null` as `Null`, and anything else as a `T`.
+///
+/// ```
+/// use nulls::Null;
+///
+/// assert_eq!(nulls::from_json_slice::<u32>(b" \n").unwrap(), Null::Undefined);
+/// assert_eq!(nulls::from_json_slice::<u32>(b"null").unwrap(), Null::Null);
+/// assert_eq!(nulls::from_json_slice::<u32>(b"42").unwrap(), Null::Value(42));
+/// assert!(nulls::from_json_slice::<u32>(b"\"42\"").is_err());
+/// ```
+pub fn from_json_slice<'de, T>(input: &'de [u8]) -> Result<Null<T>, serde_json::Error>
+where
+    T: Deserialize<'de>,
+{
+    if input.iter().all(u8::is_ascii_whitespace) {
+        Ok(Null::Undefined)
+    } else {
+        serde_json::from_slice(input)
+    }
+}
+
+pub fn from_json_str<'de, T>(input: &'de str) -> Result<Null<T>, serde_json::Error>
+where
+    T: Deserialize<'de>,
+{
+    from_json_slice(input.as_bytes())
+}
+
+/// Helper for map fields such as `HashMap<String, Null<T>>`, typically used for JSON
+/// merge-patch documents. `Undefined` entries are dropped on serialization, and keys
+/// absent on deserialization read back as `Undefined` through [`Null::from_entry`].
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Patch {
+///     #[serde(with = "nulls::serde::map_skip_undefined")]
+///     labels: BTreeMap<String, Null<String>>,
+/// }
+///
+/// let labels = BTreeMap::from([
+///     ("env".to_string(), nulls::new("prod".to_string())),
+///     ("team".to_string(), nulls::null()),
+///     ("owner".to_string(), nulls::undefined()),
+/// ]);
+///
+/// let json = serde_json::to_string(&Patch { labels }).unwrap();
+/// assert_eq!(json, r#"{"labels":{"env":"prod","team":null}}"#);
+///
+/// let patch: Patch = serde_json::from_str(&json).unwrap();
+/// assert_eq!(Null::from_entry(patch.labels.get("team")), Null::Null);
+/// assert_eq!(Null::from_entry(patch.labels.get("owner")), Null::Undefined);
+/// ```
+pub mod map_skip_undefined {
+    use super::*;
+
+    pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a M: IntoIterator<Item = (&'a K, &'a Null<V>)>,
+        K: Serialize + 'a,
+        V: Serialize + 'a,
+        S: Serializer,
+    {
+        serializer.collect_map(map.into_iter().filter(|(_, value)| !value.is_undefined()))
+    }
+
+    pub fn deserialize<'de, M, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        M::deserialize(deserializer)
+    }
+}
+
+/// Helper for a `#[serde(flatten)]` field of type `Null<Map<String, Value>>` that collects
+/// the members no other field claims, such as members written by an older version of the
+/// struct, so that they survive a round trip. The field is `Undefined` when there are none.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+/// use serde_json::{Map, Value};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Patch {
+///     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+///     name: Null<String>,
+///     #[serde(flatten, with = "nulls::serde::overflow")]
+///     extra: Null<Map<String, Value>>,
+/// }
+///
+/// let patch: Patch = serde_json::from_str(r#"{"name":"nulls","nick":null}"#).unwrap();
+/// assert_eq!(patch.extra.value().and_then(|extra| extra.get("nick")), Some(&Value::Null));
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"name":"nulls","nick":null}"#);
+///
+/// let patch: Patch = serde_json::from_str(r#"{"name":"nulls"}"#).unwrap();
+/// assert!(patch.extra.is_undefined());
+/// ```
+pub mod overflow {
+    use super::*;
+    use serde_json::Map;
+
+    pub fn serialize<S>(value: &Null<Map<String, Value>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Null::Value(map) => map.serialize(serializer),
+            Null::Null | Null::Undefined => serializer.collect_map(std::iter::empty::<(String, Value)>()),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Null<Map<String, Value>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = Map::deserialize(deserializer)?;
+        Ok(if map.is_empty() { Null::Undefined } else { Null::Value(map) })
+    }
+}
+
+/// Explicit spelling of the default `Deserialize` impl: a value that fails to
+/// deserialize into `T` is reported as an error.
+pub mod strict {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Null::deserialize(deserializer)
+    }
+}
+
+/// Opt-in lenient deserialization: a value that fails to deserialize into `T`
+/// silently becomes `Undefined` instead of an error. The nested modules coerce
+/// common JavaScript representations instead. Binary formats are read strictly, since
+/// they carry the [`compact`] encoding written by `Null` itself.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Patch {
+///     #[serde(default, with = "nulls::serde::lenient")]
+///     age: Null<i32>,
+/// }
+///
+/// let patch: Patch = serde_json::from_str(r#"{"age":"abc"}"#).unwrap();
+/// assert!(patch.age.is_undefined());
+/// ```
+pub mod lenient {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        // Binary formats carry the compact encoding written by `Null` itself, which a
+        // `Value` cannot buffer (and which rejects raw bytes), so they are read strictly.
+        if !deserializer.is_human_readable() {
+            return Null::deserialize(deserializer);
+        }
+
+        match Value::deserialize(deserializer) {
+            Ok(Value::Null) => Ok(Null::Null),
+            Ok(json) => Ok(T::deserialize(json).map_or(Null::Undefined, Null::Value)),
+            Err(_) => Ok(Null::Undefined),
+        }
+    }
+
+    /// Accepts a number or a numeric string such as `"42"` for a `Null<T>` field.
+    /// Missing and `null` are still told apart, and a malformed string is an error.
+    ///
+    /// ```
+    /// use nulls::Null;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Patch {
+    ///     #[serde(default, with = "nulls::serde::lenient::string_or_number")]
+    ///     age: Null<u32>,
+    /// }
+    ///
+    /// let patch: Patch = serde_json::from_str(r#"{"age":"42"}"#).unwrap();
+    /// assert_eq!(patch.age, Null::Value(42));
+    ///
+    /// let patch: Patch = serde_json::from_str(r#"{"age":42}"#).unwrap();
+    /// assert_eq!(patch.age, Null::Value(42));
+    ///
+    /// let patch: Patch = serde_json::from_str(r#"{"age":null}"#).unwrap();
+    /// assert_eq!(patch.age, Null::Null);
+    /// ```
+    pub mod string_or_number {
+        use super::*;
+        use std::fmt::Display;
+        use std::str::FromStr;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrNumber<T> {
+            Number(T),
+            String(String),
+        }
+
+        pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Serialize,
+            S: Serializer,
+        {
+            value.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+        where
+            T: Deserialize<'de> + FromStr,
+            T::Err: Display,
+            D: Deserializer<'de>,
+        {
+            Null::<StringOrNumber<T>>::deserialize(deserializer)?
+                .map_value(|value| match value {
+                    StringOrNumber::Number(value) => Ok(value),
+                    StringOrNumber::String(value) => value.trim().parse().map_err(::serde::de::Error::custom),
+                })
+                .transpose()
+        }
+    }
+
+    /// Accepts `true`/`false` as well as `0`/`1` for a `Null<bool>` field.
+    ///
+    /// ```
+    /// use nulls::Null;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Patch {
+    ///     #[serde(default, with = "nulls::serde::lenient::bool_from_int")]
+    ///     active: Null<bool>,
+    /// }
+    ///
+    /// let patch: Patch = serde_json::from_str(r#"{"active":1}"#).unwrap();
+    /// assert_eq!(patch.active, Null::Value(true));
+    ///
+    /// let patch: Patch = serde_json::from_str(r#"{"active":false}"#).unwrap();
+    /// assert_eq!(patch.active, Null::Value(false));
+    ///
+    /// assert!(serde_json::from_str::<Patch>(r#"{"active":2}"#).is_err());
+    /// ```
+    pub mod bool_from_int {
+        use super::*;
+
+        struct BoolFromInt(bool);
+
+        impl<'de> Deserialize<'de> for BoolFromInt {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(BoolFromIntVisitor)
+            }
+        }
+
+        struct BoolFromIntVisitor;
+
+        impl Visitor<'_> for BoolFromIntVisitor {
+            type Value = BoolFromInt;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a boolean, 0 or 1")
+            }
+
+            fn visit_bool<E: ::serde::de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                Ok(BoolFromInt(value))
+            }
+
+            fn visit_i64<E: ::serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                match value {
+                    0 => Ok(BoolFromInt(false)),
+                    1 => Ok(BoolFromInt(true)),
+                    _ => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+                }
+            }
+
+            fn visit_u64<E: ::serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                match value {
+                    0 => Ok(BoolFromInt(false)),
+                    1 => Ok(BoolFromInt(true)),
+                    _ => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
+                }
+            }
+        }
+
+        pub fn serialize<S: Serializer>(value: &Null<bool>, serializer: S) -> Result<S::Ok, S::Error> {
+            value.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Null<bool>, D::Error> {
+            Null::<BoolFromInt>::deserialize(deserializer).map(|value| value.map_value(|value| value.0))
+        }
+    }
+}
+
+/// Field helper that rejects `Undefined` on serialization regardless of the thread policy.
+pub mod error_on_undefined {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Null::Undefined => Err(::serde::ser::Error::custom(NullError::Undefined)),
+            value => value.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Null::deserialize(deserializer)
+    }
+}
+
+/// Lossless externally tagged representation that keeps all three states apart,
+/// e.g. for persisting patches to a queue or event log.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Event {
+///     #[serde(default, with = "nulls::serde::tagged")]
+///     name: Null<String>,
+/// }
+///
+/// for (name, json) in [
+///     (Null::Undefined, r#"{"name":{"undefined":null}}"#),
+///     (Null::Null, r#"{"name":{"null":null}}"#),
+///     (nulls::new("nulls".to_string()), r#"{"name":{"value":"nulls"}}"#),
+/// ] {
+///     let event = Event { name };
+///     assert_eq!(serde_json::to_string(&event).unwrap(), json);
+///     assert_eq!(serde_json::from_str::<Event>(json).unwrap(), event);
+/// }
+/// ```
+pub mod tagged {
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "lowercase")]
+    enum TaggedRef<'a, T> {
+        Undefined(()),
+        Null(()),
+        Value(&'a T),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Tagged<T> {
+        Undefined(()),
+        Null(()),
+        Value(T),
+    }
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Null::Undefined => TaggedRef::<T>::Undefined(()),
+            Null::Null => TaggedRef::Null(()),
+            Null::Value(value) => TaggedRef::Value(value),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(match Tagged::deserialize(deserializer)? {
+            Tagged::Undefined(()) => Null::Undefined,
+            Tagged::Null(()) => Null::Null,
+            Tagged::Value(value) => Null::Value(value),
+        })
+    }
+}
+
+/// Same wire representation as `serde_with::rust::double_option`, so a field can move
+/// from `Option<Option<T>>` to `Null<T>` without changing its JSON contract. Like its
+/// `serde_with` counterpart, it must be paired with `default` and `skip_serializing_if`.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Patch {
+///     #[serde(
+///         default,
+///         with = "nulls::serde::double_option",
+///         skip_serializing_if = "nulls::serde::is_undefined"
+///     )]
+///     name: Null<String>,
+/// }
+///
+/// let patch: Patch = serde_json::from_str(r#"{"name":null}"#).unwrap();
+/// assert_eq!(patch.name.into_double_option(), Some(None));
+/// ```
+pub mod double_option {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Null::Value(value) => serializer.serialize_some(value),
+            _ => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Null::from_option)
+    }
+}
+
+/// TOML has no null, so this helper spells an explicit clear as an empty table and
+/// leaves `Undefined` out: a missing key is `Undefined`, `key = {}` is `Null`, and
+/// anything else is the value. Pair it with `default` and `skip_serializing_if`.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Config {
+///     #[serde(default, with = "nulls::serde::empty_table", skip_serializing_if = "nulls::serde::is_undefined")]
+///     name: Null<String>,
+///     #[serde(default, with = "nulls::serde::empty_table", skip_serializing_if = "nulls::serde::is_undefined")]
+///     port: Null<u16>,
+///     #[serde(default, with = "nulls::serde::empty_table", skip_serializing_if = "nulls::serde::is_undefined")]
+///     proxy: Null<String>,
+/// }
+///
+/// let config: Config = toml::from_str("name = \"nulls\"\nproxy = {}").unwrap();
+/// assert_eq!(config, Config { name: nulls::new("nulls".into()), port: Null::Undefined, proxy: Null::Null });
+///
+/// let text = toml::to_string(&config).unwrap();
+/// assert_eq!(toml::from_str::<Config>(&text).unwrap(), config);
+/// ```
+///
+/// An empty table always reads as `Null`, so a `T` that is itself a table with only
+/// optional keys cannot be set to its empty form through this helper.
+pub mod empty_table {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Empty {}
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr<T> {
+        Clear(Empty),
+        Value(T),
+    }
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Null::Value(value) => value.serialize(serializer),
+            Null::Null | Null::Undefined => serializer.collect_map(std::iter::empty::<((), ())>()),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Clear(Empty {}) => Null::Null,
+            Repr::Value(value) => Null::Value(value),
+        })
+    }
+}
+
+/// Compact binary encoding: a one-byte tag (`0` for `Undefined`, `1` for `Null`,
+/// `2` for `Value`) followed by the payload for `Value` only. This is what binary
+/// formats get by default; the module forces it for human-readable formats too.
+/// A bare nil or unit in place of the tag reads as `Null`.
+///
+/// With MessagePack, this works the same whether structs are written as arrays
+/// (`rmp_serde::to_vec`) or maps (`rmp_serde::to_vec_named`), and for binary payloads
+/// such as `serde_bytes::ByteBuf`. In the named form, skipping `Undefined` fields keeps
+/// a missing key apart from a `nil` sent by another producer:
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+/// use serde_bytes::ByteBuf;
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Patch {
+///     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+///     name: Null<String>,
+///     #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+///     avatar: Null<ByteBuf>,
+/// }
+///
+/// let patch = Patch { name: nulls::null(), avatar: nulls::new(ByteBuf::from(vec![0xff])) };
+/// let bytes = rmp_serde::to_vec_named(&patch).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<Patch>(&bytes).unwrap(), patch);
+///
+/// let patch = Patch { name: nulls::new("nulls".into()), avatar: nulls::undefined() };
+/// let bytes = rmp_serde::to_vec_named(&patch).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<Patch>(&bytes).unwrap(), patch);
+///
+/// // {"name": nil} and {} from a plain MessagePack producer.
+/// let bytes = [0x81, 0xa4, b'n', b'a', b'm', b'e', 0xc0];
+/// let patch = rmp_serde::from_slice::<Patch>(&bytes).unwrap();
+/// assert_eq!((patch.name, patch.avatar), (Null::Null, Null::Undefined));
+///
+/// let patch = rmp_serde::from_slice::<Patch>(&[0x80]).unwrap();
+/// assert_eq!((patch.name, patch.avatar), (Null::Undefined, Null::Undefined));
+/// ```
+///
+/// The array form has no keys to leave out, so `skip_serializing_if` must not be used
+/// there; every state, `Undefined` included, is written as its tag:
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Patch {
+///     name: Null<String>,
+///     bio: Null<String>,
+///     age: Null<u32>,
+/// }
+///
+/// let patch = Patch { name: nulls::new("nulls".into()), bio: nulls::null(), age: nulls::undefined() };
+/// let bytes = rmp_serde::to_vec(&patch).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<Patch>(&bytes).unwrap(), patch);
+/// ```
+pub mod compact {
+    use super::*;
+
+    const UNDEFINED: u8 = 0;
+    const NULL: u8 = 1;
+    const VALUE: u8 = 2;
+
+    pub fn serialize<T, S>(value: &Null<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        let mut tuple = match value {
+            Null::Value(_) => serializer.serialize_tuple(2)?,
+            _ => serializer.serialize_tuple(1)?,
+        };
+
+        match value {
+            Null::Value(value) => {
+                tuple.serialize_element(&VALUE)?;
+                tuple.serialize_element(value)?;
+            }
+            Null::Null => tuple.serialize_element(&NULL)?,
+            Null::Undefined => tuple.serialize_element(&UNDEFINED)?,
+        }
+
+        tuple.end()
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Null<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, CompactVisitor(PhantomData))
+    }
+
+    pub(crate) struct CompactVisitor<S>(pub(crate) S);
+
+    impl<'de, S> Visitor<'de> for CompactVisitor<S>
+    where S: DeserializeSeed<'de>,
+    {
+        type Value = Null<S::Value>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a tag followed by an optional value")
+        }
+
+        fn visit_none<E: ::serde::de::Error>(self) -> Result<Self::Value, E> {
+            Ok(Null::Null)
+        }
+
+        fn visit_unit<E: ::serde::de::Error>(self) -> Result<Self::Value, E> {
+            Ok(Null::Null)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            use ::serde::de::Error;
+
+            let tag: u8 = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(0, &"a tag followed by an optional value"))?;
+
+            match tag {
+                UNDEFINED => Ok(Null::Undefined),
+                NULL => Ok(Null::Null),
+                VALUE => seq
+                    .next_element_seed(self.0)?
+                    .map(Null::Value)
+                    .ok_or_else(|| A::Error::invalid_length(1, &"a tag followed by a value")),
+                tag => Err(A::Error::invalid_value(Unexpected::Unsigned(tag.into()), &"a tag of 0, 1 or 2")),
+            }
+        }
+    }
+}
+
+impl<T> Null<T> {
+    /// Builds a `Null` from an entry of a raw JSON object: a missing entry is `Undefined`,
+    /// `null` is `Null`, and anything else is parsed into `T`.
+    pub fn try_from_json_value(value: Option<&Value>) -> Result<Null<T>, serde_json::Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        match value {
+            None => Ok(Null::Undefined),
+            Some(Value::Null) => Ok(Null::Null),
+            Some(value) => T::deserialize(value).map(Null::Value),
+        }
+    }
+
+    /// Like [`Null::try_from_json_value`], but a value that does not parse into `T`
+    /// becomes `Undefined`.
+    pub fn from_json_value(value: Option<&Value>) -> Null<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        Self::try_from_json_value(value).unwrap_or(Null::Undefined)
+    }
+
+    /// Converts back into a raw JSON object entry: `None` for `Undefined`, so the key
+    /// can be left out, and `Some(Value::Null)` for `Null`.
+    ///
+    /// ```
+    /// use nulls::Null;
+    /// use serde_json::{json, Value};
+    ///
+    /// assert_eq!(Null::<u32>::Undefined.to_json_value(), None);
+    /// assert_eq!(Null::<u32>::Null.to_json_value(), Some(Value::Null));
+    /// assert_eq!(nulls::new(42).to_json_value(), Some(json!(42)));
+    /// ```
+    pub fn to_json_value(&self) -> Option<Value>
+    where
+        T: Clone + Into<Value>,
+    {
+        match self {
+            Null::Undefined => None,
+            Null::Null => Some(Value::Null),
+            Null::Value(value) => Some(value.clone().into()),
+        }
+    }
+
+    /// Like [`Null::to_json_value`], but for any `T: Serialize`, failing where
+    /// `serde_json::to_value` does.
+    ///
+    /// ```
+    /// use nulls::Null;
+    /// use serde_json::json;
+    ///
+    /// assert_eq!(nulls::new([1, 2]).try_to_json_value().unwrap(), Some(json!([1, 2])));
+    ///
+    /// let key = std::collections::HashMap::from([(vec![1u8], 1)]);
+    /// assert!(nulls::new(key).try_to_json_value().is_err());
+    /// ```
+    pub fn try_to_json_value(&self) -> Result<Option<Value>, serde_json::Error>
+    where
+        T: Serialize,
+    {
+        match self {
+            Null::Undefined => Ok(None),
+            Null::Null => Ok(Some(Value::Null)),
+            Null::Value(value) => serde_json::to_value(value).map(Some),
+        }
+    }
+}
+
+/// Lifts a field `with`-module for `T` into one for `Null<T>`, so attributes such as
+/// `#[serde(with = "chrono::serde::ts_seconds")]` keep working on nullable fields.
+///
+/// ```
+/// use nulls::Null;
+/// use serde::{Deserialize, Serialize};
+///
+/// mod cents {
+///     use serde::{Deserialize, Deserializer, Serializer};
+///
+///     pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+///         serializer.serialize_i64((value * 100.0).round() as i64)
+///     }
+///
+///     pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+///         i64::deserialize(deserializer).map(|cents| cents as f64 / 100.0)
+///     }
+/// }
+///
+/// nulls::with_inner!(mod null_cents, cents, f64);
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Patch {
+///     #[serde(default, with = "null_cents")]
+///     price: Null<f64>,
+/// }
+///
+/// # fn main() {
+/// let patch: Patch = serde_json::from_str(r#"{"price":1250}"#).unwrap();
+/// assert_eq!(patch.price, Null::Value(12.5));
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"price":1250}"#);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! with_inner {
+    ($vis:vis mod $name:ident, $($inner:ident)::+, $ty:ty $(,)?) => {
+        $vis mod $name {
+            // Brings the caller's items into scope for `$ty` and the inner module. Everything
+            // below is spelled out in full so that none of them, such as a `Result` alias,
+            // can take the place of the names used here.
+            #[allow(unused_imports)]
+            use super::*;
+
+            pub fn serialize<S>(
+                value: &$crate::Null<$ty>,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::serde::__serde::Serializer,
+            {
+                struct With<'a>(&'a $ty);
+
+                impl $crate::serde::__serde::Serialize for With<'_> {
+                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                    where
+                        S: $crate::serde::__serde::Serializer,
+                    {
+                        $($inner)::+::serialize(self.0, serializer)
+                    }
+                }
+
+                $crate::serde::__serde::Serialize::serialize(&value.as_ref().map_value(With), serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> ::core::result::Result<$crate::Null<$ty>, D::Error>
+            where
+                D: $crate::serde::__serde::Deserializer<'de>,
+            {
+                struct With($ty);
+
+                impl<'de> $crate::serde::__serde::Deserialize<'de> for With {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: $crate::serde::__serde::Deserializer<'de>,
+                    {
+                        $($inner)::+::deserialize(deserializer).map(With)
+                    }
+                }
+
+                <$crate::Null<With> as $crate::serde::__serde::Deserialize<'de>>::deserialize(deserializer)
+                    .map(|value| value.map_value(|with| with.0))
+            }
+        }
+    };
+}
@@ -0,0 +1,542 @@
+use ::serde::ser::{
+    Error, Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use ::serde::{Serialize, Serializer};
+use std::fmt::Display;
+
+use super::{with_undefined_policy, UndefinedPolicy};
+
+/// Message of the error an `Undefined` fails with under [`UndefinedPolicy::Omit`]. Struct
+/// fields and map entries written through [`OmitUndefined`] recognize it and leave the
+/// entry out instead.
+pub(super) const NOT_OMITTED: &str = "`Undefined` can only be omitted from a struct field or map entry";
+
+/// Returns `true` if `value` fails with [`NOT_OMITTED`] when serialized, without
+/// serializing anything past its first call.
+fn is_undefined<T: ?Sized + Serialize>(value: &T) -> bool {
+    matches!(value.serialize(Probe), Err(Probed::Undefined))
+}
+
+/// Serializes `T` with [`UndefinedPolicy::Omit`], leaving every `Undefined` struct field
+/// and map entry out, for callers that cannot put `skip_serializing_if` on each field.
+///
+/// An `Undefined` anywhere else, such as in a sequence or as a map value written apart from
+/// its key, has nothing to leave out and fails serialization. So does every `Undefined` in
+/// a binary format, which writes struct fields by position.
+///
+/// ```
+/// use nulls::serde::OmitUndefined;
+/// use nulls::Null;
+/// use serde::Serialize;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Serialize)]
+/// struct Profile {
+///     bio: Null<String>,
+///     tags: Vec<Null<String>>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: Null<String>,
+///     email: Null<String>,
+///     profile: Profile,
+///     labels: BTreeMap<String, Null<String>>,
+/// }
+///
+/// let mut user = User {
+///     name: nulls::new("nulls".into()),
+///     email: Null::Undefined,
+///     profile: Profile { bio: Null::Undefined, tags: vec![Null::Null] },
+///     labels: BTreeMap::from([("env".into(), Null::Null), ("team".into(), Null::Undefined)]),
+/// };
+///
+/// assert_eq!(
+///     serde_json::to_string(&OmitUndefined(&user)).unwrap(),
+///     r#"{"name":"nulls","profile":{"tags":[null]},"labels":{"env":null}}"#,
+/// );
+///
+/// user.profile.tags.push(Null::Undefined);
+/// assert!(serde_json::to_string(&OmitUndefined(&user)).is_err());
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub struct OmitUndefined<T>(pub T);
+
+impl<T: Serialize> Serialize for OmitUndefined<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        with_undefined_policy(UndefinedPolicy::Omit, || {
+            if serializer.is_human_readable() {
+                self.0.serialize(Omit(serializer))
+            } else {
+                self.0.serialize(serializer)
+            }
+        })
+    }
+}
+
+/// A nested value, serialized with the same omission as its container.
+struct Nested<'a, T: ?Sized>(&'a T);
+
+impl<T: ?Sized + Serialize> Serialize for Nested<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(Omit(serializer))
+    }
+}
+
+/// Forwards everything to `S`, leaving `Undefined` out of structs and maps.
+struct Omit<S>(S);
+
+/// Forwards to a compound serializer of `S`, wrapping every element in [`Nested`].
+struct Compound<C>(C);
+
+impl<S: Serializer> Serializer for Omit<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<S::Ok, S::Error> {
+        self.0.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        self.0.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        self.0.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<S::Ok, S::Error> {
+        self.0.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> {
+        self.0.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> {
+        self.0.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(&Nested(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, index: u32, variant: &'static str) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, &Nested(value))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_variant(name, index, variant, &Nested(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.0.serialize_seq(len).map(Compound)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.0.serialize_tuple(len).map(Compound)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.0.serialize_tuple_struct(name, len).map(Compound)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.0.serialize_tuple_variant(name, index, variant, len).map(Compound)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.0.serialize_map(len).map(Compound)
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(Compound)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.0.serialize_struct_variant(name, index, variant, len).map(Compound)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+impl<C: SerializeSeq> SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_element(&Nested(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeTuple> SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_element(&Nested(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeTupleStruct> SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_field(&Nested(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeTupleVariant> SerializeTupleVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_field(&Nested(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+/// Only whole entries can be left out: an `Undefined` written with `serialize_value` after
+/// its key fails, as the key has already been written.
+impl<C: SerializeMap> SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.0.serialize_key(&Nested(key))
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_value(&Nested(value))
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), C::Error>
+    where
+        K: ?Sized + Serialize,
+        V: ?Sized + Serialize,
+    {
+        if is_undefined(value) {
+            return Ok(());
+        }
+
+        self.0.serialize_entry(&Nested(key), &Nested(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeStruct> SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error> {
+        if is_undefined(value) {
+            return self.0.skip_field(key);
+        }
+
+        self.0.serialize_field(key, &Nested(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.0.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeStructVariant> SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), C::Error> {
+        if is_undefined(value) {
+            return self.0.skip_field(key);
+        }
+
+        self.0.serialize_field(key, &Nested(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.0.skip_field(key)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+/// Stops at the first call, answering whether it was the error an `Undefined` fails with.
+struct Probe;
+
+#[derive(Debug)]
+enum Probed {
+    Undefined,
+    Other,
+}
+
+impl Display for Probed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Probed::Undefined => f.write_str(NOT_OMITTED),
+            Probed::Other => f.write_str("not undefined"),
+        }
+    }
+}
+
+impl std::error::Error for Probed {}
+
+impl Error for Probed {
+    fn custom<T: Display>(msg: T) -> Self {
+        if msg.to_string() == NOT_OMITTED {
+            Probed::Undefined
+        } else {
+            Probed::Other
+        }
+    }
+}
+
+impl Serializer for Probe {
+    type Ok = ();
+    type Error = Probed;
+    type SerializeSeq = Impossible<(), Probed>;
+    type SerializeTuple = Impossible<(), Probed>;
+    type SerializeTupleStruct = Impossible<(), Probed>;
+    type SerializeTupleVariant = Impossible<(), Probed>;
+    type SerializeMap = Impossible<(), Probed>;
+    type SerializeStruct = Impossible<(), Probed>;
+    type SerializeStructVariant = Impossible<(), Probed>;
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_str(self, _: &str) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<(), Probed> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), Probed> {
+        Ok(())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Probed> {
+        Err(Probed::Other)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Probed> {
+        Err(Probed::Other)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, Probed> {
+        Err(Probed::Other)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Probed> {
+        Err(Probed::Other)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Probed> {
+        Err(Probed::Other)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Probed> {
+        Err(Probed::Other)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Probed> {
+        Err(Probed::Other)
+    }
+}
@@ -0,0 +1,55 @@
+//! simd-json fast path. `Null` deserializes directly through simd-json's serde
+//! deserializer, so no `serde_json::Value` is built along the way.
+
+use ::serde::de::DeserializeOwned;
+use ::serde::{Deserialize, Serialize};
+use simd_json::prelude::*;
+use simd_json::{BorrowedValue, Error, OwnedValue};
+
+use crate::Null;
+
+/// Parses a complete JSON document with simd-json. The input buffer is modified in place.
+pub fn from_slice<'de, T>(input: &'de mut [u8]) -> Result<Null<T>, Error>
+where
+    T: Deserialize<'de>,
+{
+    simd_json::serde::from_slice(input)
+}
+
+impl<T> Null<T> {
+    /// simd-json counterpart of [`Null::try_from_json_value`].
+    pub fn try_from_simd_value(value: Option<&OwnedValue>) -> Result<Null<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        match value {
+            None => Ok(Null::Undefined),
+            Some(value) if value.is_null() => Ok(Null::Null),
+            Some(value) => simd_json::serde::from_refowned_value(value).map(Null::Value),
+        }
+    }
+
+    /// Borrowing variant of [`Null::try_from_simd_value`] for simd-json's `BorrowedValue`.
+    pub fn try_from_simd_borrowed_value<'de>(value: Option<&'de BorrowedValue<'de>>) -> Result<Null<T>, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        match value {
+            None => Ok(Null::Undefined),
+            Some(value) if value.is_null() => Ok(Null::Null),
+            Some(value) => simd_json::serde::from_refborrowed_value(value).map(Null::Value),
+        }
+    }
+
+    /// simd-json counterpart of [`Null::try_to_json_value`].
+    pub fn try_to_simd_value(&self) -> Result<Option<OwnedValue>, Error>
+    where
+        T: Serialize,
+    {
+        match self {
+            Null::Undefined => Ok(None),
+            Null::Null => Ok(Some(OwnedValue::null())),
+            Null::Value(value) => simd_json::serde::to_owned_value(value).map(Some),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Driver-independent SQL fragments for partial updates.
+//!
+//! The functions here take `(column, Null<V>)` pairs, where `V` is whatever dynamic value
+//! type the driver binds (`rusqlite::types::Value`, `serde_json::Value`, an enum of your
+//! own, ...), and return the SQL text together with the values to bind in order. A
+//! `None` in that list means "bind NULL".
+//!
+//! Column names are written as given and are not escaped; only values are bound.
+//!
+//! ```
+//! use nulls::sql::{set_clause, Placeholder};
+//! use nulls::Null;
+//!
+//! let fields = [
+//!     ("name", nulls::new("nulls")),
+//!     ("email", Null::Undefined),
+//!     ("bio", Null::Null),
+//! ];
+//!
+//! let (sql, values) = set_clause(fields, Placeholder::Dollar, 1);
+//! assert_eq!(sql, "name = $1, bio = $2");
+//! assert_eq!(values, [Some("nulls"), None]);
+//!
+//! let (sql, _) = set_clause([("name", nulls::new(1))], Placeholder::AtP, 3);
+//! assert_eq!(sql, "name = @p3");
+//! ```
+
+use crate::Null;
+
+/// How bound parameters are spelled in the generated SQL.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub enum Placeholder {
+    /// `$1`, `$2`, ... as used by Postgres.
+    #[default]
+    Dollar,
+    /// `?` as used by SQLite and MySQL.
+    Question,
+    /// `@p1`, `@p2`, ... as used by SQL Server.
+    AtP,
+}
+
+impl Placeholder {
+    /// Spells the placeholder for the 1-based parameter `index`.
+    pub fn format(self, index: usize) -> String {
+        match self {
+            Placeholder::Dollar => format!("${}", index),
+            Placeholder::Question => "?".to_string(),
+            Placeholder::AtP => format!("@p{}", index),
+        }
+    }
+}
+
+/// Builds `column = <placeholder>` assignments joined with `, `, skipping `Undefined`
+/// fields. Numbering starts at `first`, so the fragment can follow earlier parameters.
+///
+/// The SQL is empty when every field is `Undefined`.
+pub fn set_clause<'c, V>(fields: impl IntoIterator<Item = (&'c str, Null<V>)>, placeholder: Placeholder, first: usize) -> (String, Vec<Option<V>>) {
+    let mut assignments = Vec::new();
+    let mut values = Vec::new();
+
+    for (column, value) in fields {
+        if value.is_undefined() {
+            continue;
+        }
+
+        assignments.push(format!("{} = {}", column, placeholder.format(first + values.len())));
+        values.push(value.into_double_option().flatten());
+    }
+
+    (assignments.join(", "), values)
+}
+
+/// Builds `(columns) VALUES (...)` for a single row, writing `DEFAULT` for `Undefined`
+/// fields. Numbering starts at `first`.
+///
+/// With no fields at all the SQL is `() VALUES ()`. MySQL accepts that as a row of defaults,
+/// but Postgres, SQLite and SQL Server reject it and spell the same row `DEFAULT VALUES`, so
+/// check for an empty field list first when targeting them.
+///
+/// ```
+/// use nulls::sql::{insert_clause, Placeholder};
+/// use nulls::Null;
+///
+/// let (sql, values) = insert_clause([("name", nulls::new("nulls")), ("bio", Null::Undefined)], Placeholder::Question, 1);
+/// assert_eq!(sql, "(name, bio) VALUES (?, DEFAULT)");
+/// assert_eq!(values, [Some("nulls")]);
+///
+/// let (sql, values) = insert_clause(std::iter::empty::<(&str, Null<i32>)>(), Placeholder::Question, 1);
+/// assert_eq!(sql, "() VALUES ()");
+/// assert!(values.is_empty());
+/// ```
+pub fn insert_clause<'c, V>(fields: impl IntoIterator<Item = (&'c str, Null<V>)>, placeholder: Placeholder, first: usize) -> (String, Vec<Option<V>>) {
+    let mut columns = Vec::new();
+    let mut placeholders = Vec::new();
+    let mut values = Vec::new();
+
+    for (column, value) in fields {
+        columns.push(column);
+
+        if value.is_undefined() {
+            placeholders.push("DEFAULT".to_string());
+        } else {
+            placeholders.push(placeholder.format(first + values.len()));
+            values.push(value.into_double_option().flatten());
+        }
+    }
+
+    (format!("({}) VALUES ({})", columns.join(", "), placeholders.join(", ")), values)
+}
@@ -0,0 +1,434 @@
+use ::sqlx::encode::IsNull;
+use ::sqlx::error::UnexpectedNullError;
+use ::sqlx::types::Json;
+use ::sqlx::query::{QueryAs, QueryScalar};
+use ::sqlx::{ColumnIndex, Database, Decode, Encode, Error, Executor, FromRow, IntoArguments, Row, Type};
+use std::future::Future;
+
+use crate::{Null, NullError};
+
+/// Implements `Type`, `Encode` and `Decode` for `Null<T>` on one database. `Value` encodes
+/// as `T` and both `Undefined` and `Null` as NULL, written by `$null` when the database
+/// needs more than `IsNull::Yes`.
+macro_rules! database {
+    ($db:ident, $type_info:ident, $value_ref:ident) => {
+        database!($db, $type_info, $value_ref, |_buf| Ok(::sqlx::encode::IsNull::Yes));
+    };
+    ($db:ident, $type_info:ident, $value_ref:ident, |$buf:ident| $null:expr) => {
+        impl<T> ::sqlx::Type<$db> for $crate::Null<T>
+            where T: ::sqlx::Type<$db>,
+        {
+            fn type_info() -> $type_info {
+                T::type_info()
+            }
+
+            fn compatible(ty: &$type_info) -> bool {
+                ::sqlx::TypeInfo::is_null(ty) || T::compatible(ty)
+            }
+        }
+
+        impl<'q, T> ::sqlx::Encode<'q, $db> for $crate::Null<T>
+            where T: ::sqlx::Encode<'q, $db> + ::sqlx::Type<$db> + 'q,
+        {
+            fn encode_by_ref(&self, $buf: &mut <$db as ::sqlx::Database>::ArgumentBuffer<'q>) -> Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
+                match self {
+                    $crate::Null::Value(ref value) => value.encode_by_ref($buf),
+                    $crate::Null::Undefined | $crate::Null::Null => $null,
+                }
+            }
+
+            fn produces(&self) -> Option<$type_info> {
+                match self {
+                    $crate::Null::Value(ref value) => value.produces(),
+                    $crate::Null::Undefined | $crate::Null::Null => Some(T::type_info()),
+                }
+            }
+
+            fn size_hint(&self) -> usize {
+                self.value().map_or(0, ::sqlx::Encode::size_hint)
+            }
+        }
+
+        impl<'r, T> ::sqlx::Decode<'r, $db> for $crate::Null<T>
+            where T: ::sqlx::Decode<'r, $db> + ::sqlx::Type<$db>,
+        {
+            fn decode(value: $value_ref<'r>) -> Result<Self, ::sqlx::error::BoxDynError> {
+                if ::sqlx::ValueRef::is_null(&value) {
+                    Ok($crate::Null::Null)
+                } else {
+                    T::decode(value).map($crate::Null::Value)
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sqlx-any")]
+mod any;
+
+#[cfg(feature = "sqlx-mysql")]
+mod mysql;
+
+#[cfg(feature = "sqlx-postgres")]
+mod postgres;
+
+#[cfg(feature = "sqlx-sqlite")]
+mod sqlite;
+
+/// Deprecated: lossy, as every error, including connection failures, becomes `Null`. Use
+/// [`Null::try_from_result`], which only treats absence as `Null`. This impl will be
+/// removed in the next major release; `#[deprecated]` cannot be put on a trait impl, so
+/// the compiler does not warn about it.
+impl<T> From<Result<T, Error>> for Null<T> {
+    fn from(value: Result<T, Error>) -> Self {
+        match value {
+            Ok(data) => Null::Value(data),
+            _ => Null::Null
+        }
+    }
+}
+
+/// Deprecated like the plain `Result` conversion. Use [`Null::try_from_result`] on
+/// `result.map(|json| json.0)`.
+impl<T> From<Result<Json<T>, Error>> for Null<T> {
+    fn from(value: Result<Json<T>, Error>) -> Self {
+        match value {
+            Ok(data) => Null::Value(data.0),
+            _ => Null::Null
+        }
+    }
+}
+
+/// Bind wrapper that refuses to encode `Undefined`, so a field the client never sent
+/// fails the query instead of silently writing NULL.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Defined<T>(pub Null<T>);
+
+impl<T> Null<T> {
+    /// Converts a sqlx result, keeping real failures as errors. For a `Json<T>` column,
+    /// pass `result.map(|json| json.0)`.
+    ///
+    /// `RowNotFound` and decoding SQL NULL into a non-nullable type become `Null`, and
+    /// `ColumnNotFound` becomes `Undefined` as with [`NullRowExt::try_get_null`]. Any
+    /// other error is returned unchanged.
+    pub fn try_from_result(result: Result<T, Error>) -> Result<Null<T>, Error> {
+        match result {
+            Ok(value) => Ok(Null::Value(value)),
+            Err(Error::RowNotFound) => Ok(Null::Null),
+            Err(Error::ColumnNotFound(_)) => Ok(Null::Undefined),
+            Err(Error::ColumnDecode { source, .. }) if source.is::<UnexpectedNullError>() => Ok(Null::Null),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Wraps `self` for binding with [`Defined`]'s strict `Undefined` handling.
+    pub fn strict(self) -> Defined<T> {
+        Defined(self)
+    }
+}
+
+impl<T> From<Null<T>> for Defined<T> {
+    fn from(value: Null<T>) -> Self {
+        Defined(value)
+    }
+}
+
+impl<T, DB> Type<DB> for Defined<T>
+    where DB: Database, Null<T>: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        Null::<T>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        Null::<T>::compatible(ty)
+    }
+}
+
+impl<'q, T, DB> Encode<'q, DB> for Defined<T>
+    where DB: Database, Null<T>: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, ::sqlx::error::BoxDynError> {
+        match self.0 {
+            Null::Undefined => Err(Box::new(NullError::Undefined)),
+            ref value => value.encode_by_ref(buf),
+        }
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.0.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+/// Binds and decodes a `Null<T>` as a JSON document (`jsonb` on Postgres), so document
+/// columns can take part in patches without wrapping values in `sqlx::types::Json`.
+///
+/// `Undefined` and `Null` both encode as SQL NULL; use [`Defined`] or skip the column
+/// when `Undefined` must not be written.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub struct NullJson<T>(pub Null<T>);
+
+impl<T> NullJson<T> {
+    /// Unwraps the inner `Null<T>`.
+    pub fn into_inner(self) -> Null<T> {
+        self.0
+    }
+}
+
+impl<T> Null<T> {
+    /// Wraps `self` for binding as a JSON document with [`NullJson`].
+    pub fn json(self) -> NullJson<T> {
+        NullJson(self)
+    }
+}
+
+impl<T> From<Null<T>> for NullJson<T> {
+    fn from(value: Null<T>) -> Self {
+        NullJson(value)
+    }
+}
+
+impl<T> From<NullJson<T>> for Null<T> {
+    fn from(value: NullJson<T>) -> Self {
+        value.0
+    }
+}
+
+impl<T> std::ops::Deref for NullJson<T> {
+    type Target = Null<T>;
+
+    fn deref(&self) -> &Null<T> {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for NullJson<T> {
+    fn deref_mut(&mut self) -> &mut Null<T> {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: ::serde::Serialize> ::serde::Serialize for NullJson<T> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ::serde::Deserialize<'de>> ::serde::Deserialize<'de> for NullJson<T> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Null::deserialize(deserializer).map(NullJson)
+    }
+}
+
+impl<T, DB> Type<DB> for NullJson<T>
+    where DB: Database, Json<T>: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        Json::<T>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        Json::<T>::compatible(ty)
+    }
+}
+
+impl<'q, T, DB> Encode<'q, DB> for NullJson<T>
+    where DB: Database, Json<T>: Type<DB>, for<'a> Json<&'a T>: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, ::sqlx::error::BoxDynError> {
+        match self.0 {
+            Null::Value(ref value) => Json(value).encode_by_ref(buf),
+            Null::Undefined | Null::Null => Ok(IsNull::Yes),
+        }
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        Some(Json::<T>::type_info())
+    }
+}
+
+impl<'r, T, DB> Decode<'r, DB> for NullJson<T>
+    where DB: Database, Option<Json<T>>: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, ::sqlx::error::BoxDynError> {
+        Ok(NullJson(match Option::<Json<T>>::decode(value)? {
+            Some(Json(value)) => Null::Value(value),
+            None => Null::Null,
+        }))
+    }
+}
+
+/// Reads `Null` columns from a row, telling an unselected column apart from SQL NULL.
+pub trait NullRowExt: Row {
+    /// Returns `Undefined` if the column is not part of the row, `Null` for SQL NULL
+    /// and `Value` otherwise. Decoding failures are still reported as errors.
+    fn try_get_null<'r, T, I>(&'r self, index: I) -> Result<Null<T>, Error>
+    where
+        I: ColumnIndex<Self>,
+        Null<T>: Decode<'r, Self::Database> + Type<Self::Database>;
+}
+
+impl<R: Row> NullRowExt for R {
+    fn try_get_null<'r, T, I>(&'r self, index: I) -> Result<Null<T>, Error>
+    where
+        I: ColumnIndex<Self>,
+        Null<T>: Decode<'r, Self::Database> + Type<Self::Database>,
+    {
+        match self.try_get(index) {
+            Err(Error::ColumnNotFound(_)) => Ok(Null::Undefined),
+            value => value,
+        }
+    }
+}
+
+/// Fetches a row as `Null` instead of folding failures into `Null`: [`fetch_null`] reads
+/// a missing row as `Null`, and [`fetch_one_null`] a NULL where the row holds a
+/// non-nullable type.
+///
+/// [`fetch_null`]: FetchNullExt::fetch_null
+/// [`fetch_one_null`]: FetchNullExt::fetch_one_null
+///
+/// ```
+/// use nulls::{FetchNullExt, Null};
+/// use sqlx::PgPool;
+///
+/// async fn nickname(pool: &PgPool, id: i64) -> Result<Null<String>, sqlx::Error> {
+///     sqlx::query_scalar("SELECT nickname FROM users WHERE id = $1")
+///         .bind(id)
+///         .fetch_null(pool)
+///         .await
+/// }
+/// ```
+pub trait FetchNullExt<'q, DB: Database>: Sized {
+    type Output;
+
+    /// Runs the query and returns the first row, or `Null` if there is none.
+    fn fetch_null<'e, 'c: 'e, E>(self, executor: E) -> impl Future<Output = Result<Null<Self::Output>, Error>> + Send + 'e
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        Self: 'e;
+
+    /// Like `fetch_one`, so a missing row is still `RowNotFound`, but decoding SQL NULL
+    /// into the non-nullable output type gives `Null` instead of an error. Drivers that
+    /// decode NULL without an error, as SQLite does for numbers and text, return what
+    /// they decode.
+    fn fetch_one_null<'e, 'c: 'e, E>(self, executor: E) -> impl Future<Output = Result<Null<Self::Output>, Error>> + Send + 'e
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        Self: 'e;
+}
+
+impl<'q, DB, O, A> FetchNullExt<'q, DB> for QueryAs<'q, DB, O, A>
+where
+    DB: Database,
+    A: 'q + Send + IntoArguments<'q, DB>,
+    O: Send + Unpin + for<'r> FromRow<'r, DB::Row>,
+{
+    type Output = O;
+
+    async fn fetch_null<'e, 'c: 'e, E>(self, executor: E) -> Result<Null<O>, Error>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        Self: 'e,
+    {
+        self.fetch_optional(executor).await.map(Null::from_option)
+    }
+
+    async fn fetch_one_null<'e, 'c: 'e, E>(self, executor: E) -> Result<Null<O>, Error>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        Self: 'e,
+    {
+        match self.fetch_one(executor).await {
+            Err(Error::ColumnDecode { source, .. }) if source.is::<UnexpectedNullError>() => Ok(Null::Null),
+            value => value.map(Null::Value),
+        }
+    }
+}
+
+impl<'q, DB, O, A> FetchNullExt<'q, DB> for QueryScalar<'q, DB, O, A>
+where
+    DB: Database,
+    O: Send + Unpin,
+    A: 'q + Send + IntoArguments<'q, DB>,
+    (O,): Send + Unpin + for<'r> FromRow<'r, DB::Row>,
+{
+    type Output = O;
+
+    async fn fetch_null<'e, 'c: 'e, E>(self, executor: E) -> Result<Null<O>, Error>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        Self: 'e,
+    {
+        self.fetch_optional(executor).await.map(Null::from_option)
+    }
+
+    async fn fetch_one_null<'e, 'c: 'e, E>(self, executor: E) -> Result<Null<O>, Error>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        Self: 'e,
+    {
+        match self.fetch_one(executor).await {
+            Err(Error::ColumnDecode { source, .. }) if source.is::<UnexpectedNullError>() => Ok(Null::Null),
+            value => value.map(Null::Value),
+        }
+    }
+}
+
+/// Implements `sqlx::FromRow` for a struct of `Null` fields, reading each column with
+/// [`NullRowExt::try_get_null`] so columns missing from the projection become
+/// `Undefined` instead of failing the whole row.
+///
+/// With `#[derive(sqlx::FromRow)]`, marking the fields `#[sqlx(default)]` has the same
+/// effect, since `Null::default()` is `Undefined`.
+///
+/// ```
+/// use nulls::Null;
+///
+/// struct UserPatch {
+///     name: Null<String>,
+///     bio: Null<String>,
+/// }
+///
+/// nulls::impl_from_row!(UserPatch { name: Null<String>, bio: Null<String> });
+///
+/// fn assert_from_row<T: for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>>() {}
+/// assert_from_row::<UserPatch>();
+/// ```
+#[macro_export]
+macro_rules! impl_from_row {
+    ($name:ident { $($field:ident : $ty:ty),* $(,)? }) => {
+        impl<'r, R> $crate::__sqlx::FromRow<'r, R> for $name
+        where
+            R: $crate::__sqlx::Row,
+            &'r str: $crate::__sqlx::ColumnIndex<R>,
+            $($ty: $crate::__sqlx::Decode<'r, R::Database> + $crate::__sqlx::Type<R::Database>,)*
+        {
+            fn from_row(row: &'r R) -> Result<Self, $crate::__sqlx::Error> {
+                use $crate::NullRowExt;
+
+                Ok(Self {
+                    $($field: row.try_get_null(stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
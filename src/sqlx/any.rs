@@ -0,0 +1,5 @@
+use ::sqlx::any::{AnyTypeInfo, AnyValueRef};
+use ::sqlx::Any;
+
+// `Any` needs the inner type of a NULL argument, which `Option`'s impl already records.
+database!(Any, AnyTypeInfo, AnyValueRef, |buf| None::<T>.encode_by_ref(buf));
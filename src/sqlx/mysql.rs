@@ -0,0 +1,4 @@
+use ::sqlx::mysql::{MySqlTypeInfo, MySqlValueRef};
+use ::sqlx::MySql;
+
+database!(MySql, MySqlTypeInfo, MySqlValueRef);
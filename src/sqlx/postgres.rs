@@ -0,0 +1,71 @@
+//! Postgres support for `Null<T>`.
+//!
+//! `Null<T>` forwards `type_info`, `compatible` and the array type info to `T`, so
+//! enums and composites deriving `sqlx::Type` bind and decode through the wrapper.
+//! Their OIDs are still resolved by name when the statement is prepared, and `Null`
+//! fields inside a composite decode SQL NULL attributes as `Null`.
+//!
+//! ```
+//! use nulls::Null;
+//! use sqlx::postgres::{PgHasArrayType, Postgres};
+//! use sqlx::{Decode, Encode, Type};
+//!
+//! #[derive(sqlx::Type)]
+//! #[sqlx(type_name = "mood", rename_all = "lowercase")]
+//! enum Mood { Happy, Sad }
+//!
+//! #[derive(sqlx::Type)]
+//! #[sqlx(type_name = "pair")]
+//! struct Pair { a: i32, b: Null<String> }
+//!
+//! fn bindable<T>()
+//!     where T: for<'q> Encode<'q, Postgres> + for<'r> Decode<'r, Postgres> + Type<Postgres> {}
+//!
+//! bindable::<Null<Mood>>();
+//! bindable::<Null<Pair>>();
+//! bindable::<Vec<Null<Mood>>>();
+//! assert_eq!(<Null<Mood> as Type<Postgres>>::type_info(), <Mood as Type<Postgres>>::type_info());
+//! assert_eq!(Null::<Pair>::array_type_info(), Pair::array_type_info());
+//! ```
+//!
+//! Ranges and intervals pass through the same way, so `tstzrange` and `interval`
+//! columns can be patched like any other:
+//!
+//! ```
+//! use sqlx::types::chrono::{DateTime, Utc};
+//! use nulls::Null;
+//! use sqlx::postgres::types::{PgInterval, PgRange};
+//! use sqlx::postgres::{PgHasArrayType, Postgres};
+//! use sqlx::{Decode, Encode, Type};
+//!
+//! fn bindable<T>()
+//!     where T: for<'q> Encode<'q, Postgres> + for<'r> Decode<'r, Postgres> + Type<Postgres> {}
+//!
+//! bindable::<Null<PgRange<DateTime<Utc>>>>();
+//! bindable::<Null<PgInterval>>();
+//! bindable::<Vec<Null<PgRange<DateTime<Utc>>>>>();
+//! assert_eq!(Null::<PgRange<DateTime<Utc>>>::type_info(), PgRange::<DateTime<Utc>>::type_info());
+//! assert_eq!(Null::<PgInterval>::array_type_info(), PgInterval::array_type_info());
+//! ```
+
+use ::sqlx::postgres::{PgHasArrayType, PgTypeInfo, PgValueRef};
+use ::sqlx::Postgres;
+
+use crate::Null;
+
+// Derived enums and composites report a name-only `PgTypeInfo` whose OID is looked up
+// on bind, so the wrapper must hand it through untouched rather than build its own.
+database!(Postgres, PgTypeInfo, PgValueRef);
+
+// Lets `Vec<Null<T>>` bind to and decode from arrays with NULL elements.
+impl<T> PgHasArrayType for Null<T>
+    where T: PgHasArrayType,
+{
+    fn array_type_info() -> PgTypeInfo {
+        T::array_type_info()
+    }
+
+    fn array_compatible(ty: &PgTypeInfo) -> bool {
+        T::array_compatible(ty)
+    }
+}
@@ -0,0 +1,4 @@
+use ::sqlx::sqlite::{SqliteTypeInfo, SqliteValueRef};
+use ::sqlx::Sqlite;
+
+database!(Sqlite, SqliteTypeInfo, SqliteValueRef);
@@ -0,0 +1,45 @@
+//! SurrealDB conversions. SurrealDB tells `NONE` (no value) apart from `NULL`, which
+//! map directly onto `Undefined` and `Null`.
+//!
+//! ```
+//! use nulls::Null;
+//! use surrealdb::sql::Value;
+//!
+//! assert_eq!(Value::from(Null::<i64>::Undefined), Value::None);
+//! assert_eq!(Value::from(Null::<i64>::Null), Value::Null);
+//! assert_eq!(Value::from(nulls::new(7_i64)), Value::from(7_i64));
+//!
+//! assert_eq!(Null::<i64>::try_from_surreal_value(Value::None).unwrap(), Null::Undefined);
+//! assert_eq!(Null::<i64>::try_from_surreal_value(Value::from(7_i64)).unwrap(), Null::Value(7));
+//! ```
+
+use ::surrealdb::sql::Value;
+
+use crate::Null;
+
+impl<T: Into<Value>> From<Null<T>> for Value {
+    fn from(value: Null<T>) -> Value {
+        match value {
+            Null::Undefined => Value::None,
+            Null::Null => Value::Null,
+            Null::Value(value) => value.into(),
+        }
+    }
+}
+
+impl<T> Null<T> {
+    /// Reads `NONE` as `Undefined`, `NULL` as `Null`, and anything else as a `T`.
+    ///
+    /// This is an inherent method rather than `TryFrom`, which would overlap with the
+    /// blanket `From<T> for Null<T>` impl.
+    pub fn try_from_surreal_value(value: Value) -> Result<Null<T>, T::Error>
+    where
+        T: TryFrom<Value>,
+    {
+        match value {
+            Value::None => Ok(Null::Undefined),
+            Value::Null => Ok(Null::Null),
+            value => T::try_from(value).map(Null::Value),
+        }
+    }
+}
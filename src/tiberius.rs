@@ -0,0 +1,44 @@
+//! Tiberius (SQL Server) conversions.
+//!
+//! SQL Server NULLs are typed, so `Null` and `Undefined` both bind the typed NULL that
+//! `None::<T>` produces; TDS has no unset parameter to map `Undefined` onto. A NULL
+//! column reads back as `Some(Null::Null)` from `Row::get` rather than `None`.
+
+use ::tiberius::{ColumnData, FromSql, FromSqlOwned, IntoSql, ToSql};
+
+use crate::Null;
+
+impl<T> ToSql for Null<T>
+    where T: ToSql, Option<T>: IntoSql<'static>,
+{
+    fn to_sql(&self) -> ColumnData<'_> {
+        match self {
+            Null::Value(ref value) => value.to_sql(),
+            Null::Undefined | Null::Null => None::<T>.into_sql(),
+        }
+    }
+}
+
+impl<'a, T> IntoSql<'a> for Null<T>
+    where Null<T>: Send + Sync, Option<T>: IntoSql<'a>,
+{
+    fn into_sql(self) -> ColumnData<'a> {
+        self.into_double_option().flatten().into_sql()
+    }
+}
+
+impl<'a, T> FromSql<'a> for Null<T>
+    where T: FromSql<'a>,
+{
+    fn from_sql(value: &'a ColumnData<'static>) -> ::tiberius::Result<Option<Self>> {
+        T::from_sql(value).map(|value| Some(Null::from_option(value)))
+    }
+}
+
+impl<T> FromSqlOwned for Null<T>
+    where T: FromSqlOwned,
+{
+    fn from_sql_owned(value: ColumnData<'static>) -> ::tiberius::Result<Option<Self>> {
+        T::from_sql_owned(value).map(|value| Some(Null::from_option(value)))
+    }
+}
@@ -0,0 +1,47 @@
+//! tokio-postgres conversions through `postgres-types`.
+//!
+//! A bind parameter in the Postgres protocol is either a value or NULL, so `Undefined`
+//! binds as NULL like `Null` and should be left out of an `UPDATE`'s `SET` list by the
+//! caller. NULL reads back as `Null`.
+
+use ::bytes::BytesMut;
+use ::postgres_types::{to_sql_checked, Format, FromSql, IsNull, ToSql, Type};
+use std::error::Error;
+
+use crate::Null;
+
+impl<T: ToSql> ToSql for Null<T> {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match self {
+            Null::Value(ref value) => value.to_sql(ty, out),
+            Null::Undefined | Null::Null => Ok(IsNull::Yes),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        T::accepts(ty)
+    }
+
+    fn encode_format(&self, ty: &Type) -> Format {
+        match self {
+            Null::Value(ref value) => value.encode_format(ty),
+            Null::Undefined | Null::Null => Format::Binary,
+        }
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Null<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        T::from_sql(ty, raw).map(Null::Value)
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(Null::Null)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        T::accepts(ty)
+    }
+}
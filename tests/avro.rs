@@ -0,0 +1,128 @@
+#![cfg(all(feature = "apache-avro", feature = "derive"))]
+
+use apache_avro::{from_avro_datum, from_value, to_avro_datum, AvroSchema, Schema};
+use nulls::{ApplyPatch, Null, Patch};
+use serde_json::json;
+
+#[allow(dead_code)]
+#[derive(Patch, ApplyPatch)]
+#[null(avro)]
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Patch)]
+#[null(avro)]
+struct User {
+    #[null(rename = "displayName", alias = "name")]
+    name: String,
+    age: Option<i32>,
+    #[null(nested)]
+    home: Address,
+    #[null(nested)]
+    work: Address,
+}
+
+fn nullable(name: &str, ty: serde_json::Value) -> serde_json::Value {
+    json!({ "name": name, "type": ["null", ty], "default": null })
+}
+
+#[test]
+fn schema_json() {
+    let mut name = nullable("displayName", json!("string"));
+    name["aliases"] = json!(["name"]);
+
+    let address = json!({
+        "type": "record",
+        "name": "AddressPatch",
+        "fields": [nullable("city", json!("string")), nullable("zip", json!("string"))],
+    });
+
+    assert_eq!(
+        serde_json::to_value(UserPatch::get_schema()).unwrap(),
+        json!({
+            "type": "record",
+            "name": "UserPatch",
+            "fields": [
+                name,
+                nullable("age", json!("int")),
+                nullable("home", address),
+                // The second use of the nested record refers to the first by name.
+                nullable("work", json!("AddressPatch")),
+            ],
+        }),
+    );
+}
+
+/// Parses the schema back from its JSON, as a schema registry would hand it out.
+fn registered() -> Schema {
+    Schema::parse_str(&serde_json::to_string(&UserPatch::get_schema()).unwrap()).unwrap()
+}
+
+fn round_trip(patch: &UserPatch) -> UserPatch {
+    let bytes = to_avro_datum(&registered(), nulls::avro::to_value(patch).unwrap()).unwrap();
+    let value = from_avro_datum(&registered(), &mut bytes.as_slice(), Some(&UserPatch::get_schema())).unwrap();
+    from_value(&value).unwrap()
+}
+
+#[test]
+fn registered_schema_matches() {
+    assert_eq!(registered().canonical_form(), UserPatch::get_schema().canonical_form());
+}
+
+#[test]
+fn values_round_trip() {
+    let patch = UserPatch {
+        name: nulls::new("nulls".into()),
+        age: Null::Null,
+        home: nulls::new(AddressPatch { city: nulls::new("Manila".into()), zip: Null::Null }),
+        work: Null::Null,
+    };
+
+    assert_eq!(round_trip(&patch), patch);
+}
+
+#[test]
+fn undefined_reads_as_the_default() {
+    let patch = UserPatch {
+        name: Null::Undefined,
+        age: nulls::new(7),
+        home: nulls::new(AddressPatch { city: Null::Undefined, zip: nulls::new("1000".into()) }),
+        work: Null::Undefined,
+    };
+
+    assert_eq!(
+        round_trip(&patch),
+        UserPatch {
+            name: Null::Null,
+            age: nulls::new(7),
+            home: nulls::new(AddressPatch { city: Null::Null, zip: nulls::new("1000".into()) }),
+            work: Null::Null,
+        },
+    );
+}
+
+#[test]
+fn older_writer_schema() {
+    let writer = Schema::parse(&json!({
+        "type": "record",
+        "name": "UserPatch",
+        "fields": [nullable("displayName", json!("string"))],
+    }))
+    .unwrap();
+
+    let record = apache_avro::types::Value::Record(vec![(
+        "displayName".into(),
+        apache_avro::types::Value::Union(1, Box::new(apache_avro::types::Value::String("nulls".into()))),
+    )]);
+    let bytes = to_avro_datum(&writer, record).unwrap();
+
+    // The fields the writer did not know read as their `null` default.
+    let value = from_avro_datum(&writer, &mut bytes.as_slice(), Some(&UserPatch::get_schema())).unwrap();
+    assert_eq!(
+        from_value::<UserPatch>(&value).unwrap(),
+        UserPatch { name: nulls::new("nulls".into()), age: Null::Null, home: Null::Null, work: Null::Null },
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "bson")]
+
+use bson::{doc, Bson};
+use nulls::bson::{update_document, NullUpdate};
+use nulls::Null;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Patch {
+    #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+    name: Null<String>,
+    #[serde(default, skip_serializing_if = "nulls::serde::is_undefined")]
+    age: Null<i32>,
+}
+
+#[test]
+fn into_bson() {
+    assert_eq!(Bson::from(nulls::new(7)), Bson::Int32(7));
+    assert_eq!(Bson::from(Null::<i32>::Null), Bson::Null);
+    assert_eq!(Bson::from(Null::<i32>::Undefined), Bson::Null);
+}
+
+#[test]
+fn document_round_trips() {
+    let patch = Patch { name: nulls::new("nulls".into()), age: Null::Null };
+    let document = bson::to_document(&patch).unwrap();
+
+    assert_eq!(document, doc! { "name": "nulls", "age": null });
+    assert_eq!(bson::from_document::<Patch>(document).unwrap(), patch);
+
+    let read: Patch = bson::from_document(doc! { "age": 7 }).unwrap();
+    assert_eq!(read, Patch { name: Null::Undefined, age: nulls::new(7) });
+}
+
+#[test]
+fn update_document_leaves_undefined_out() {
+    let patch = Patch { name: Null::Undefined, age: Null::Null };
+
+    assert_eq!(update_document(&patch, NullUpdate::Set).unwrap(), doc! { "$set": { "age": null } });
+    assert_eq!(update_document(&patch, NullUpdate::Unset).unwrap(), doc! { "$unset": { "age": "" } });
+
+    let patch = Patch { name: Null::Undefined, age: Null::Undefined };
+    assert_eq!(update_document(&patch, NullUpdate::Set).unwrap(), doc! {});
+}
+
+#[test]
+fn update_document_rejects_undefined_that_is_not_skipped() {
+    #[derive(Serialize)]
+    struct Patch {
+        name: Null<String>,
+        age: Null<i32>,
+    }
+
+    let patch = Patch { name: nulls::new("nulls".into()), age: Null::Undefined };
+    assert!(update_document(&patch, NullUpdate::Unset).is_err());
+
+    let patch = Patch { name: nulls::new("nulls".into()), age: Null::Null };
+    assert_eq!(
+        update_document(&patch, NullUpdate::Unset).unwrap(),
+        doc! { "$set": { "name": "nulls" }, "$unset": { "age": "" } },
+    );
+}
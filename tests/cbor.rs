@@ -0,0 +1,81 @@
+#![cfg(feature = "cbor")]
+
+use nulls::Null;
+use serde::{Deserialize, Serialize};
+
+fn write<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Patch {
+    name: Null<String>,
+    bio: Null<String>,
+    age: Null<u32>,
+    nick: Null<Option<String>>,
+}
+
+#[test]
+fn struct_fields_round_trip() {
+    let patches = [
+        Patch { name: nulls::new("nulls".into()), bio: Null::Null, age: Null::Undefined, nick: nulls::new(None) },
+        Patch { name: Null::Undefined, bio: nulls::new(String::new()), age: nulls::new(0), nick: Null::Null },
+        Patch { name: Null::Null, bio: Null::Undefined, age: Null::Null, nick: nulls::new(Some("n".into())) },
+    ];
+
+    for patch in patches {
+        let bytes = write(&patch);
+        assert_eq!(ciborium::from_reader::<Patch, _>(bytes.as_slice()).unwrap(), patch);
+    }
+}
+
+#[test]
+fn standalone_round_trip() {
+    for value in [nulls::new("nulls".to_string()), nulls::new(String::new()), Null::Null, Null::Undefined] {
+        let bytes = nulls::cbor::to_vec(&value).unwrap();
+        assert_eq!(nulls::cbor::from_slice::<String>(&bytes).unwrap(), value);
+    }
+
+    for value in [nulls::new(vec![1u8, 2]), nulls::new(Vec::new()), Null::Null, Null::Undefined] {
+        let bytes = nulls::cbor::to_vec(&value).unwrap();
+        assert_eq!(nulls::cbor::from_slice::<Vec<u8>>(&bytes).unwrap(), value);
+    }
+}
+
+#[test]
+fn standalone_rejects_values_written_as_null() {
+    assert!(nulls::cbor::to_vec(&nulls::new(None::<u8>)).is_err());
+    assert!(nulls::cbor::to_vec(&nulls::new(())).is_err());
+    assert_eq!(nulls::cbor::to_vec(&nulls::new(Some(1u8))).unwrap(), [0x01]);
+}
+
+#[test]
+fn standalone_compact_round_trip() {
+    // Through serde, a standalone `Null` uses the compact encoding, which keeps
+    // `Null<Option<T>>` apart too.
+    for value in [nulls::new(None::<u8>), nulls::new(Some(1)), Null::Null, Null::Undefined] {
+        let bytes = write(&value);
+        assert_eq!(ciborium::from_reader::<Null<Option<u8>>, _>(bytes.as_slice()).unwrap(), value);
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Plain {
+    #[serde(default, with = "nulls::serde::double_option", skip_serializing_if = "nulls::serde::is_undefined")]
+    name: Null<String>,
+}
+
+#[test]
+fn double_option_fields_round_trip() {
+    for (name, bytes) in [
+        (Null::Undefined, vec![0xa0]),
+        (Null::Null, vec![0xa1, 0x64, b'n', b'a', b'm', b'e', 0xf6]),
+        (nulls::new("a".to_string()), vec![0xa1, 0x64, b'n', b'a', b'm', b'e', 0x61, b'a']),
+    ] {
+        let plain = Plain { name };
+        assert_eq!(write(&plain), bytes);
+        assert_eq!(ciborium::from_reader::<Plain, _>(bytes.as_slice()).unwrap(), plain);
+    }
+}
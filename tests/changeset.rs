@@ -0,0 +1,26 @@
+#![cfg(all(feature = "derive", feature = "serde"))]
+
+use nulls::changeset::Change;
+use nulls::{ApplyPatch, Null, Patch, Record};
+use serde_json::json;
+
+#[derive(Patch, ApplyPatch, Record)]
+struct User {
+    name: String,
+    bio: Option<String>,
+}
+
+#[test]
+fn derive_from_crate_root() {
+    use nulls::changeset::Record as _;
+
+    let mut user = User { name: "nulls".into(), bio: None };
+    let patch = UserPatch { name: nulls::new("patched".into()), bio: Null::Undefined };
+
+    let changes = user.apply_recorded(patch).unwrap();
+    assert_eq!(
+        changes.into_iter().collect::<Vec<_>>(),
+        [Change { field: "name".into(), old: json!("nulls"), new: json!("patched") }],
+    );
+    assert_eq!(user.name, "patched");
+}
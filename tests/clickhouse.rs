@@ -0,0 +1,86 @@
+#![cfg(feature = "clickhouse")]
+
+use clickhouse::test::{handlers, Mock};
+use clickhouse::{Client, Row};
+use nulls::Null;
+use serde::{Deserialize, Serialize};
+
+#[derive(Row, Serialize, Deserialize, PartialEq, Debug)]
+struct ChangeEvent {
+    id: u64,
+    #[serde(with = "nulls::clickhouse::nullable")]
+    email: Null<String>,
+    #[serde(with = "nulls::clickhouse::nullable")]
+    age: Null<u8>,
+}
+
+// The layout of a `Nullable(T)` column, as written for `Option<T>`.
+#[derive(Row, Serialize, Deserialize, PartialEq, Debug)]
+struct OptionEvent {
+    id: u64,
+    email: Option<String>,
+    age: Option<u8>,
+}
+
+fn events() -> Vec<ChangeEvent> {
+    vec![
+        ChangeEvent { id: 1, email: nulls::new("a@example.com".into()), age: nulls::new(7) },
+        ChangeEvent { id: 2, email: Null::Null, age: Null::Null },
+        ChangeEvent { id: 3, email: Null::Undefined, age: Null::Undefined },
+    ]
+}
+
+#[tokio::test]
+async fn insert_writes_nullable_columns() {
+    let mock = Mock::new();
+    let client = Client::default().with_url(mock.url());
+    let recording = mock.add(handlers::record::<OptionEvent>());
+
+    let mut insert = client.insert("events").unwrap();
+    for event in events() {
+        insert.write(&event).await.unwrap();
+    }
+    insert.end().await.unwrap();
+
+    let rows: Vec<OptionEvent> = recording.collect().await;
+    assert_eq!(
+        rows,
+        [
+            OptionEvent { id: 1, email: Some("a@example.com".into()), age: Some(7) },
+            OptionEvent { id: 2, email: None, age: None },
+            OptionEvent { id: 3, email: None, age: None },
+        ],
+    );
+}
+
+#[tokio::test]
+async fn fetch_reads_nullable_columns() {
+    let mock = Mock::new();
+    let client = Client::default().with_url(mock.url());
+    mock.add(handlers::provide(events()));
+
+    let rows = client.query("SELECT ?fields FROM events").fetch_all::<ChangeEvent>().await.unwrap();
+    assert_eq!(
+        rows,
+        [
+            ChangeEvent { id: 1, email: nulls::new("a@example.com".into()), age: nulls::new(7) },
+            ChangeEvent { id: 2, email: Null::Null, age: Null::Null },
+            ChangeEvent { id: 3, email: Null::Null, age: Null::Null },
+        ],
+    );
+}
+
+#[tokio::test]
+async fn undefined_policy_error() {
+    use nulls::serde::{with_undefined_policy, UndefinedPolicy};
+
+    let mock = Mock::new();
+    let client = Client::default().with_url(mock.url());
+
+    let mut insert = client.insert("events").unwrap();
+    let event = ChangeEvent { id: 1, email: Null::Undefined, age: Null::Null };
+
+    // Rows are serialized when `write` is called, not when it is awaited.
+    let write = with_undefined_policy(UndefinedPolicy::Error, || insert.write(&event));
+    assert!(write.await.is_err());
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "diesel")]
+
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Nullable, Text};
+use nulls::Null;
+
+diesel::table! {
+    users (id) {
+        id -> Integer,
+        name -> Nullable<Text>,
+        age -> Nullable<Integer>,
+    }
+}
+
+fn connect() -> SqliteConnection {
+    let mut conn = SqliteConnection::establish(":memory:").unwrap();
+    diesel::sql_query("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)")
+        .execute(&mut conn)
+        .unwrap();
+    conn
+}
+
+fn read(conn: &mut SqliteConnection) -> Vec<(i32, Null<String>, Null<i32>)> {
+    users::table.select((users::id, users::name, users::age)).order(users::id).load(conn).unwrap()
+}
+
+#[test]
+fn round_trips() {
+    let mut conn = connect();
+
+    diesel::insert_into(users::table)
+        .values(&[
+            (users::id.eq(1), users::name.eq(nulls::new("nulls".to_string())), users::age.eq(nulls::new(7))),
+            (users::id.eq(2), users::name.eq(Null::Null), users::age.eq(Null::Null)),
+            (users::id.eq(3), users::name.eq(Null::Undefined), users::age.eq(Null::Undefined)),
+        ])
+        .execute(&mut conn)
+        .unwrap();
+
+    assert_eq!(
+        read(&mut conn),
+        [(1, nulls::new("nulls".to_string()), nulls::new(7)), (2, Null::Null, Null::Null), (3, Null::Null, Null::Null)],
+    );
+}
+
+#[test]
+fn bind_by_reference() {
+    let mut conn = connect();
+    let name = nulls::new("nulls".to_string());
+
+    diesel::insert_into(users::table)
+        .values((users::id.eq(1), users::name.eq(&name)))
+        .execute(&mut conn)
+        .unwrap();
+
+    let read: Null<String> = users::table.select(users::name).first(&mut conn).unwrap();
+    assert_eq!(read, name);
+}
+
+#[test]
+fn assign_skips_undefined() {
+    let mut conn = connect();
+
+    diesel::insert_into(users::table)
+        .values((users::id.eq(1), users::name.eq("nulls"), users::age.eq(7)))
+        .execute(&mut conn)
+        .unwrap();
+
+    diesel::update(users::table.find(1))
+        .set((Null::<String>::Undefined.assign(users::name), Null::<i32>::Null.assign(users::age)))
+        .execute(&mut conn)
+        .unwrap();
+    assert_eq!(read(&mut conn), [(1, nulls::new("nulls".to_string()), Null::Null)]);
+
+    diesel::update(users::table.find(1))
+        .set((nulls::new("patched".to_string()).assign(users::name), Null::<i32>::Undefined.assign(users::age)))
+        .execute(&mut conn)
+        .unwrap();
+    assert_eq!(read(&mut conn), [(1, nulls::new("patched".to_string()), Null::Null)]);
+
+    assert!(Null::<i32>::Undefined.assign(users::age).is_none());
+}
+
+#[test]
+fn raw_sql_query() {
+    #[derive(QueryableByName, PartialEq, Debug)]
+    struct Row {
+        #[diesel(sql_type = Nullable<Text>)]
+        name: Null<String>,
+        #[diesel(sql_type = Nullable<Integer>)]
+        age: Null<i32>,
+    }
+
+    let mut conn = connect();
+    let rows: Vec<Row> = diesel::sql_query("SELECT 'nulls' AS name, NULL AS age").load(&mut conn).unwrap();
+
+    assert_eq!(rows, [Row { name: nulls::new("nulls".to_string()), age: Null::Null }]);
+}
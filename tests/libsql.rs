@@ -0,0 +1,63 @@
+#![cfg(feature = "libsql")]
+
+// Opening a database needs libsql's `core` feature, whose bundled SQLite cannot be linked
+// next to the one `sqlx-sqlite` and `rusqlite` bundle, so these tests stop at the values a
+// connection would bind and return.
+
+use libsql::params::IntoValue;
+use libsql::Value;
+use nulls::Null;
+
+type Row = (Null<String>, Null<i64>, Null<f64>, Null<Vec<u8>>);
+
+fn bind((name, age, score, data): Row) -> [Value; 4] {
+    [name.into_value().unwrap(), age.into_value().unwrap(), score.into_value().unwrap(), data.into_value().unwrap()]
+}
+
+fn read([name, age, score, data]: [Value; 4]) -> Row {
+    (
+        Null::try_from_libsql_value(name).unwrap(),
+        Null::try_from_libsql_value(age).unwrap(),
+        Null::try_from_libsql_value(score).unwrap(),
+        Null::try_from_libsql_value(data).unwrap(),
+    )
+}
+
+#[test]
+fn binds() {
+    assert_eq!(
+        bind((nulls::new("nulls".into()), nulls::new(7), nulls::new(0.5), nulls::new(vec![1, 2]))),
+        [Value::Text("nulls".into()), Value::Integer(7), Value::Real(0.5), Value::Blob(vec![1, 2])],
+    );
+    assert_eq!(bind((Null::Null, Null::Null, Null::Null, Null::Null)), [Value::Null, Value::Null, Value::Null, Value::Null]);
+    assert_eq!(
+        bind((Null::Undefined, Null::Undefined, Null::Undefined, Null::Undefined)),
+        [Value::Null, Value::Null, Value::Null, Value::Null],
+    );
+}
+
+#[test]
+fn round_trips() {
+    let rows: [Row; 3] = [
+        (nulls::new("nulls".into()), nulls::new(7), nulls::new(0.5), nulls::new(vec![1, 2])),
+        (Null::Null, Null::Null, Null::Null, Null::Null),
+        (Null::Undefined, Null::Undefined, Null::Undefined, Null::Undefined),
+    ];
+
+    let read = rows.map(|row| read(bind(row)));
+
+    assert_eq!(
+        read,
+        [
+            (nulls::new("nulls".into()), nulls::new(7), nulls::new(0.5), nulls::new(vec![1, 2])),
+            (Null::Null, Null::Null, Null::Null, Null::Null),
+            (Null::Null, Null::Null, Null::Null, Null::Null),
+        ],
+    );
+}
+
+#[test]
+fn type_mismatch() {
+    assert!(Null::<i64>::try_from_libsql_value(Value::Text("nulls".into())).is_err());
+    assert!(Null::<String>::try_from_libsql_value(Value::Integer(7)).is_err());
+}
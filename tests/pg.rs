@@ -0,0 +1,255 @@
+#![cfg(feature = "sqlx-postgres")]
+
+use nulls::pg::{BuildError, CopyBinaryWriter, InsertBuilder, UpdateBuilder};
+use nulls::Null;
+use sqlx::{Arguments, Execute};
+
+fn arguments<'q>(query: &mut impl Execute<'q, sqlx::Postgres>) -> usize {
+    query.take_arguments().unwrap().map_or(0, |arguments| arguments.len())
+}
+
+#[test]
+fn update_builds_once() {
+    let mut update = UpdateBuilder::new("users");
+    update.set("name", nulls::new("a")).filter("id", 1);
+
+    let mut query = update.build().unwrap();
+    assert_eq!(query.sql(), "UPDATE users SET name = $1 WHERE id = $2");
+    assert_eq!(arguments(&mut query), 2);
+
+    assert!(matches!(update.build(), Err(BuildError::Built)));
+}
+
+#[test]
+fn insert_multiple_rows() {
+    let mut insert = InsertBuilder::new("users");
+    insert.row()
+        .value("name", nulls::new("a"))
+        .value("bio", Null::<String>::Null);
+    insert.row()
+        .value("bio", nulls::new("b"))
+        .value("email", nulls::new("b@example.com"));
+
+    let mut query = insert.build().unwrap();
+    assert_eq!(
+        query.sql(),
+        "INSERT INTO users (name, bio, email) VALUES ($1, $2, DEFAULT), (DEFAULT, $3, $4)",
+    );
+    assert_eq!(arguments(&mut query), 4);
+}
+
+#[test]
+fn insert_undefined_is_default() {
+    let mut insert = InsertBuilder::new("users");
+    insert.row()
+        .value("name", Null::<String>::Undefined)
+        .value("bio", nulls::new("a"));
+    insert.row()
+        .value("name", Null::<String>::Undefined)
+        .value("bio", Null::<String>::Undefined);
+
+    let mut query = insert.build().unwrap();
+    assert_eq!(query.sql(), "INSERT INTO users (name, bio) VALUES (DEFAULT, $1), (DEFAULT, DEFAULT)");
+    assert_eq!(arguments(&mut query), 1);
+
+    assert!(matches!(insert.build(), Err(BuildError::Built)));
+}
+
+#[test]
+fn insert_without_columns() {
+    let mut insert = InsertBuilder::new("users");
+    insert.row().value("name", Null::<String>::Undefined);
+
+    // The column is still listed, so the row is written as DEFAULT.
+    assert_eq!(insert.build().unwrap().sql(), "INSERT INTO users (name) VALUES (DEFAULT)");
+
+    let mut insert = InsertBuilder::new("users");
+    insert.row();
+    assert_eq!(insert.build().unwrap().sql(), "INSERT INTO users DEFAULT VALUES");
+
+    let mut insert = InsertBuilder::new("users");
+    insert.row().row();
+    assert!(matches!(insert.build(), Err(BuildError::Empty)));
+
+    assert!(matches!(InsertBuilder::new("users").build(), Err(BuildError::Empty)));
+}
+
+#[test]
+fn insert_duplicate_column() {
+    let mut insert = InsertBuilder::new("users");
+    insert.row()
+        .value("name", nulls::new("a"))
+        .value("name", nulls::new("b"));
+
+    assert!(matches!(insert.build(), Err(BuildError::Duplicate(column)) if column == "name"));
+    assert!(matches!(insert.build(), Err(BuildError::Built)));
+
+    // The same column in different rows is not a duplicate.
+    let mut insert = InsertBuilder::new("users");
+    insert.row().value("name", nulls::new("a"));
+    insert.row().value("name", nulls::new("b"));
+
+    let mut query = insert.build().unwrap();
+    assert_eq!(query.sql(), "INSERT INTO users (name) VALUES ($1), ($2)");
+    assert_eq!(arguments(&mut query), 2);
+}
+
+#[test]
+fn insert_returning() {
+    let mut insert = InsertBuilder::new("users");
+    insert.value("name", nulls::new("a")).returning("id, name");
+
+    assert_eq!(insert.build().unwrap().sql(), "INSERT INTO users (name) VALUES ($1) RETURNING id, name");
+}
+
+#[test]
+fn upsert_assigns_columns_defined_in_every_row() {
+    let mut upsert = InsertBuilder::new("users");
+    upsert.row()
+        .value("id", nulls::new(1))
+        .value("name", nulls::new("a"))
+        .value("bio", nulls::new("a"));
+    upsert.row()
+        .value("id", nulls::new(2))
+        .value("name", Null::<String>::Null)
+        .value("bio", Null::<String>::Undefined);
+    upsert.on_conflict(&["id"]).returning("id");
+
+    let mut query = upsert.build().unwrap();
+    assert_eq!(
+        query.sql(),
+        "INSERT INTO users (id, name, bio) VALUES ($1, $2, $3), ($4, $5, DEFAULT) \
+         ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name RETURNING id",
+    );
+    assert_eq!(arguments(&mut query), 5);
+}
+
+#[test]
+fn upsert_composite_target() {
+    let mut upsert = InsertBuilder::new("pages");
+    upsert.row()
+        .value("tenant_id", nulls::new(1))
+        .value("slug", nulls::new("home"))
+        .value("title", nulls::new("Home"));
+    upsert.on_conflict(&["tenant_id", "slug"]);
+
+    assert_eq!(
+        upsert.build().unwrap().sql(),
+        "INSERT INTO pages (tenant_id, slug, title) VALUES ($1, $2, $3) \
+         ON CONFLICT (tenant_id, slug) DO UPDATE SET title = EXCLUDED.title",
+    );
+}
+
+#[test]
+fn upsert_without_assignments_does_nothing() {
+    let mut upsert = InsertBuilder::new("users");
+    upsert.row()
+        .value("id", nulls::new(1))
+        .value("name", Null::<String>::Undefined);
+    upsert.on_conflict(&["id"]);
+
+    assert_eq!(
+        upsert.build().unwrap().sql(),
+        "INSERT INTO users (id, name) VALUES ($1, DEFAULT) ON CONFLICT (id) DO NOTHING",
+    );
+}
+
+#[test]
+fn upsert_quoted_target() {
+    let mut upsert = InsertBuilder::new("pages");
+    upsert.row()
+        .value(r#""a,b""#, nulls::new(1))
+        .value("title", nulls::new("Home"));
+    upsert.on_conflict(&[r#""a,b""#]);
+
+    assert_eq!(
+        upsert.build().unwrap().sql(),
+        r#"INSERT INTO pages ("a,b", title) VALUES ($1, $2) ON CONFLICT ("a,b") DO UPDATE SET title = EXCLUDED.title"#,
+    );
+}
+
+#[test]
+fn upsert_on_constraint() {
+    let mut upsert = InsertBuilder::new("users");
+    upsert.row()
+        .value("id", nulls::new(1))
+        .value("name", nulls::new("a"))
+        .value("bio", Null::<String>::Undefined);
+    upsert.on_constraint("users_pkey");
+
+    assert_eq!(
+        upsert.build().unwrap().sql(),
+        "INSERT INTO users (id, name, bio) VALUES ($1, $2, DEFAULT) \
+         ON CONFLICT ON CONSTRAINT users_pkey DO UPDATE SET id = EXCLUDED.id, name = EXCLUDED.name",
+    );
+}
+
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+#[test]
+fn copy_binary_fields() {
+    let mut copy = CopyBinaryWriter::new();
+    copy.row()
+        .field(nulls::new(7_i32)).unwrap()
+        .field(Null::<String>::Null).unwrap()
+        .field(Null::<String>::Undefined).unwrap()
+        .field(nulls::new("ab")).unwrap();
+
+    let mut expected = HEADER.to_vec();
+    expected.extend_from_slice(&[0, 4]);
+    expected.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 7]);
+    expected.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    expected.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    expected.extend_from_slice(&[0, 0, 0, 2, b'a', b'b']);
+    expected.extend_from_slice(&[0xff, 0xff]);
+
+    assert_eq!(copy.finish(), expected);
+}
+
+#[test]
+fn copy_binary_rows() {
+    let mut copy = CopyBinaryWriter::new();
+    // The first field starts a row on its own.
+    copy.field(nulls::new(1_i16)).unwrap();
+    copy.row().field(Null::<i16>::Null).unwrap();
+
+    let mut expected = HEADER.to_vec();
+    expected.extend_from_slice(&[0, 1, 0, 0, 0, 2, 0, 1]);
+    expected.extend_from_slice(&[0, 1, 0xff, 0xff, 0xff, 0xff]);
+    expected.extend_from_slice(&[0xff, 0xff]);
+
+    assert_eq!(copy.finish(), expected);
+    assert_eq!(CopyBinaryWriter::new().finish(), [HEADER, &[0xff, 0xff]].concat());
+}
+
+#[derive(sqlx::Type)]
+#[sqlx(type_name = "mood", rename_all = "lowercase")]
+enum Mood {
+    Happy,
+}
+
+#[test]
+fn copy_binary_rejects_types_without_oid() {
+    let mut copy = CopyBinaryWriter::new();
+    copy.row().field(nulls::new(1_i32)).unwrap();
+
+    assert!(copy.field(nulls::new(Mood::Happy)).is_err());
+
+    // Nothing was written for the rejected field.
+    let mut expected = HEADER.to_vec();
+    expected.extend_from_slice(&[0, 1, 0, 0, 0, 4, 0, 0, 0, 1]);
+    expected.extend_from_slice(&[0xff, 0xff]);
+    assert_eq!(copy.finish(), expected);
+}
+
+#[test]
+fn copy_binary_rejects_too_many_fields() {
+    let mut copy = CopyBinaryWriter::new();
+    copy.row();
+
+    for _ in 0..i16::MAX {
+        copy.field(Null::<i32>::Null).unwrap();
+    }
+
+    assert!(copy.field(Null::<i32>::Null).is_err());
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "redis")]
+
+use nulls::Null;
+use redis::{FromRedisValue, ToRedisArgs, Value};
+
+fn round_trip<T: ToRedisArgs + FromRedisValue>(value: &Null<T>) -> Null<T> {
+    let mut args = value.to_redis_args();
+    assert_eq!(args.len(), 1);
+    assert_eq!(value.num_of_args(), 1);
+
+    Null::from_redis_value(&Value::BulkString(args.remove(0))).unwrap()
+}
+
+#[test]
+fn round_trips() {
+    assert_eq!(round_trip(&nulls::new(42)), nulls::new(42));
+    assert_eq!(round_trip(&Null::<i32>::Null), Null::Null);
+    assert_eq!(round_trip(&Null::<i32>::Undefined), Null::Undefined);
+
+    assert_eq!(round_trip(&nulls::new("nulls".to_string())), nulls::new("nulls".to_string()));
+    assert_eq!(round_trip(&nulls::new(String::new())), nulls::new(String::new()));
+    assert_eq!(round_trip(&Null::<String>::Null), Null::Null);
+
+    // Bytes are a single argument, whatever their length.
+    assert_eq!(round_trip(&nulls::new(Vec::<u8>::new())), nulls::new(Vec::new()));
+    assert_eq!(round_trip(&nulls::new(vec![b'n'])), nulls::new(vec![b'n']));
+}
+
+#[test]
+fn encoding() {
+    assert_eq!(Null::<i32>::Undefined.to_redis_args(), [b"u".to_vec()]);
+    assert_eq!(Null::<i32>::Null.to_redis_args(), [b"n".to_vec()]);
+    assert_eq!(nulls::new("n").to_redis_args(), [b"vn".to_vec()]);
+}
+
+#[test]
+fn decoding() {
+    assert_eq!(Null::<i32>::from_redis_value(&Value::Nil).unwrap(), Null::Undefined);
+    assert!(Null::<i32>::from_redis_value(&Value::BulkString(b"42".to_vec())).is_err());
+    assert!(Null::<i32>::from_redis_value(&Value::BulkString(Vec::new())).is_err());
+    assert!(Null::<i32>::from_redis_value(&Value::BulkString(b"nx".to_vec())).is_err());
+    assert!(Null::<i32>::from_redis_value(&Value::Int(42)).is_err());
+}
+
+#[test]
+fn sequence_of_nulls() {
+    let values = vec![nulls::new(1), Null::Null, Null::Undefined];
+    let args = values.to_redis_args();
+    assert_eq!(args, [b"v1".to_vec(), b"n".to_vec(), b"u".to_vec()]);
+
+    let read = Vec::<Null<i32>>::from_redis_value(&Value::Array(args.into_iter().map(Value::BulkString).collect()));
+    assert_eq!(read.unwrap(), values);
+}
+
+#[test]
+fn multiple_args_round_trip() {
+    assert_eq!(round_trip(&nulls::new(vec!["a".to_string(), "b".to_string()])), nulls::new(vec!["a".into(), "b".into()]));
+    assert_eq!(round_trip(&nulls::new(vec![String::new(), "n".into()])), nulls::new(vec![String::new(), "n".into()]));
+    assert_eq!(round_trip(&nulls::new((1, "two".to_string()))), nulls::new((1, "two".to_string())));
+}
+
+#[test]
+fn empty_args_round_trip() {
+    assert_eq!(nulls::new(Vec::<String>::new()).to_redis_args(), [b"l".to_vec()]);
+    assert_eq!(round_trip(&nulls::new(Vec::<String>::new())), nulls::new(Vec::new()));
+}
+
+#[test]
+fn truncated_lists_are_rejected() {
+    let truncated = [b"l".as_slice(), &2u64.to_be_bytes(), b"a"].concat();
+    assert!(Null::<Vec<String>>::from_redis_value(&Value::BulkString(truncated)).is_err());
+    assert!(Null::<Vec<String>>::from_redis_value(&Value::BulkString(b"l\0\0\0\0".to_vec())).is_err());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "rusqlite")]
+
+use nulls::Null;
+use rusqlite::{params, Connection};
+
+type Row = (i64, Null<String>, Null<i64>, Null<Vec<u8>>);
+
+#[test]
+fn round_trips() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute("CREATE TABLE nulls_test (id INTEGER PRIMARY KEY, name TEXT, age INTEGER, data BLOB)", [])
+        .unwrap();
+
+    let rows: [Row; 3] = [
+        (1, nulls::new("nulls".into()), nulls::new(7), nulls::new(vec![1, 2])),
+        (2, Null::Null, Null::Null, Null::Null),
+        (3, Null::Undefined, Null::Undefined, Null::Undefined),
+    ];
+
+    for (id, name, age, data) in &rows {
+        conn.execute("INSERT INTO nulls_test (id, name, age, data) VALUES (?1, ?2, ?3, ?4)", params![id, name, age, data])
+            .unwrap();
+    }
+
+    let mut statement = conn.prepare("SELECT id, name, age, data FROM nulls_test ORDER BY id").unwrap();
+    let read = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .unwrap()
+        .collect::<Result<Vec<Row>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        read,
+        [
+            (1, nulls::new("nulls".into()), nulls::new(7), nulls::new(vec![1, 2])),
+            (2, Null::Null, Null::Null, Null::Null),
+            (3, Null::Null, Null::Null, Null::Null),
+        ],
+    );
+}
+
+#[test]
+fn type_mismatch() {
+    let conn = Connection::open_in_memory().unwrap();
+
+    let read = conn.query_row("SELECT 'nulls'", [], |row| row.get::<_, Null<i64>>(0));
+    assert!(read.is_err());
+}
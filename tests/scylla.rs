@@ -0,0 +1,53 @@
+#![cfg(feature = "scylla")]
+
+use nulls::Null;
+use scylla::cluster::metadata::{ColumnType, NativeType};
+use scylla::deserialize::value::DeserializeValue;
+use scylla::deserialize::FrameSlice;
+use scylla::serialize::value::SerializeValue;
+use scylla::serialize::writers::CellWriter;
+
+fn serialize<T: SerializeValue>(value: &Null<T>, typ: &ColumnType) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    value.serialize(typ, CellWriter::new(&mut buffer)).unwrap();
+    buffer
+}
+
+fn round_trip<T>(value: &Null<T>, typ: &'static ColumnType<'static>) -> Null<T>
+where
+    T: SerializeValue + for<'frame> DeserializeValue<'frame, 'static>,
+{
+    let buffer = serialize(value, typ);
+    let (length, contents) = buffer.split_at(4);
+    let cell = (i32::from_be_bytes(length.try_into().unwrap()) >= 0).then(|| FrameSlice::new_borrowed(contents));
+
+    Null::<T>::type_check(typ).unwrap();
+    Null::<T>::deserialize(typ, cell).unwrap()
+}
+
+const INT: ColumnType<'static> = ColumnType::Native(NativeType::Int);
+const TEXT: ColumnType<'static> = ColumnType::Native(NativeType::Text);
+
+#[test]
+fn cells() {
+    assert_eq!(serialize(&nulls::new(7_i32), &INT), [0, 0, 0, 4, 0, 0, 0, 7]);
+    assert_eq!(serialize(&Null::<i32>::Null, &INT), (-1_i32).to_be_bytes());
+    assert_eq!(serialize(&Null::<i32>::Undefined, &INT), (-2_i32).to_be_bytes());
+}
+
+#[test]
+fn round_trips() {
+    assert_eq!(round_trip(&nulls::new(7_i32), &INT), nulls::new(7));
+    assert_eq!(round_trip(&Null::<i32>::Null, &INT), Null::Null);
+    assert_eq!(round_trip(&Null::<i32>::Undefined, &INT), Null::Null);
+
+    assert_eq!(round_trip(&nulls::new("nulls".to_string()), &TEXT), nulls::new("nulls".to_string()));
+    assert_eq!(round_trip(&Null::<String>::Null, &TEXT), Null::Null);
+}
+
+#[test]
+fn type_mismatch() {
+    let mut buffer = Vec::new();
+    assert!(nulls::new(7_i32).serialize(&TEXT, CellWriter::new(&mut buffer)).is_err());
+    assert!(Null::<i32>::type_check(&TEXT).is_err());
+}
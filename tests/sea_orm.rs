@@ -0,0 +1,28 @@
+#![cfg(feature = "sea-orm")]
+
+use nulls::Null;
+use sea_orm::ActiveValue;
+
+#[test]
+fn into_active_value() {
+    assert_eq!(ActiveValue::from(nulls::new(7_i32)), ActiveValue::Set(Some(7)));
+    assert_eq!(ActiveValue::from(Null::<i32>::Null), ActiveValue::Set(None));
+    assert_eq!(ActiveValue::from(Null::<i32>::Undefined), ActiveValue::<Option<i32>>::NotSet);
+}
+
+#[test]
+fn from_active_value() {
+    assert_eq!(Null::<i32>::from(ActiveValue::Set(Some(7))), nulls::new(7));
+    assert_eq!(Null::<i32>::from(ActiveValue::Set(None)), Null::Null);
+    assert_eq!(Null::<i32>::from(ActiveValue::Unchanged(Some(7))), nulls::new(7));
+    assert_eq!(Null::<i32>::from(ActiveValue::Unchanged(None)), Null::Null);
+    assert_eq!(Null::<i32>::from(ActiveValue::NotSet), Null::Undefined);
+}
+
+#[test]
+fn round_trips() {
+    for value in [nulls::new("nulls".to_string()), Null::Null, Null::Undefined] {
+        let active: ActiveValue<Option<String>> = value.clone().into();
+        assert_eq!(Null::<String>::from(active), value);
+    }
+}
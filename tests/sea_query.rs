@@ -0,0 +1,31 @@
+#![cfg(feature = "sea-query")]
+
+use nulls::Null;
+use sea_query::{ColumnType, Value, ValueType};
+
+#[test]
+fn into_value() {
+    assert_eq!(Value::from(nulls::new(7_i32)), Value::Int(Some(7)));
+    assert_eq!(Value::from(Null::<i32>::Null), Value::Int(None));
+    assert_eq!(Value::from(Null::<i32>::Undefined), Value::Int(None));
+
+    assert_eq!(Value::from(nulls::new("nulls".to_string())), Value::String(Some(Box::new("nulls".into()))));
+    assert_eq!(Value::from(Null::<String>::Null), Value::String(None));
+}
+
+#[test]
+fn round_trips() {
+    let round_trip = |value: Null<i32>| <Null<i32> as ValueType>::try_from(Value::from(value)).unwrap();
+
+    assert_eq!(round_trip(nulls::new(7)), nulls::new(7));
+    assert_eq!(round_trip(Null::Null), Null::Null);
+    assert_eq!(round_trip(Null::Undefined), Null::Null);
+}
+
+#[test]
+fn value_type() {
+    assert!(<Null<String> as ValueType>::try_from(Value::Int(Some(7))).is_err());
+    assert!(<Null<i32> as ValueType>::is_option());
+    assert_eq!(<Null<i32> as ValueType>::type_name(), "Null<i32>");
+    assert_eq!(<Null<i32> as ValueType>::column_type(), ColumnType::Integer);
+}
@@ -0,0 +1,255 @@
+#![cfg(feature = "serde")]
+
+use nulls::Null;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Patch {
+    name: Null<String>,
+    #[serde(default)]
+    bio: Null<String>,
+}
+
+#[test]
+fn missing_field_with_default_is_undefined() {
+    let patch: Patch = serde_json::from_str(r#"{"name":null}"#).unwrap();
+    assert_eq!(patch, Patch { name: Null::Null, bio: Null::Undefined });
+
+    let patch: Patch = serde_json::from_str(r#"{"name":"nulls","bio":null}"#).unwrap();
+    assert_eq!(patch, Patch { name: nulls::new("nulls".into()), bio: Null::Null });
+}
+
+#[test]
+fn missing_field_without_default() {
+    // serde reads a missing field through `deserialize_option`, which cannot tell it
+    // apart from an explicit `null`.
+    let patch: Patch = serde_json::from_str("{}").unwrap();
+    assert_eq!(patch, Patch { name: Null::Null, bio: Null::Undefined });
+
+    let patch: Patch = rmp_serde::from_slice(&[0x80]).unwrap();
+    assert_eq!(patch, Patch { name: Null::Null, bio: Null::Undefined });
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Message {
+    name: Null<String>,
+    bio: Null<String>,
+    age: Null<u32>,
+}
+
+fn message() -> Message {
+    Message { name: nulls::new("nulls".into()), bio: Null::Null, age: Null::Undefined }
+}
+
+#[test]
+fn bincode_round_trip() {
+    let bytes = bincode::serialize(&message()).unwrap();
+    assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), message());
+}
+
+#[test]
+fn postcard_round_trip() {
+    let bytes = postcard::to_allocvec(&message()).unwrap();
+    assert_eq!(postcard::from_bytes::<Message>(&bytes).unwrap(), message());
+}
+
+#[derive(Serialize)]
+struct Inner {
+    #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+    bio: Null<String>,
+}
+
+#[derive(Serialize)]
+enum Event {
+    Renamed {
+        #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+        name: Null<String>,
+        #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+        by: Null<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct Outer {
+    #[serde(skip_serializing_if = "nulls::serde::is_undefined")]
+    name: Null<String>,
+    #[serde(flatten)]
+    inner: Inner,
+    event: Event,
+    nick: Option<Null<String>>,
+}
+
+fn outer(nick: Option<Null<String>>) -> Outer {
+    Outer {
+        name: Null::Undefined,
+        inner: Inner { bio: Null::Undefined },
+        event: Event::Renamed { name: nulls::new("nulls".into()), by: Null::Undefined },
+        nick,
+    }
+}
+
+#[test]
+fn skip_serializing_if_omits_fields() {
+    assert_eq!(serde_json::to_string(&outer(None)).unwrap(), r#"{"event":{"Renamed":{"name":"nulls"}},"nick":null}"#);
+    assert_eq!(
+        serde_json::to_string(&outer(Some(Null::Undefined))).unwrap(),
+        r#"{"event":{"Renamed":{"name":"nulls"}},"nick":null}"#,
+    );
+}
+
+#[test]
+fn error_on_undefined_rejects_fields_that_are_not_skipped() {
+    use nulls::serde::ErrorOnUndefined;
+
+    assert!(serde_json::to_string(&ErrorOnUndefined(outer(Some(Null::Undefined)))).is_err());
+    assert_eq!(
+        serde_json::to_string(&ErrorOnUndefined(outer(Some(Null::Null)))).unwrap(),
+        r#"{"event":{"Renamed":{"name":"nulls"}},"nick":null}"#,
+    );
+}
+
+#[derive(Serialize)]
+struct Plain {
+    name: Null<String>,
+    #[serde(flatten)]
+    message: Message,
+    tags: Vec<Null<String>>,
+}
+
+#[test]
+fn omit_undefined_leaves_fields_out() {
+    use nulls::serde::OmitUndefined;
+
+    let plain = Plain { name: Null::Undefined, message: message(), tags: vec![Null::Null] };
+    assert_eq!(
+        serde_json::to_string(&OmitUndefined(&plain)).unwrap(),
+        r#"{"name":"nulls","bio":null,"tags":[null]}"#,
+    );
+    assert_eq!(
+        serde_json::to_string(&OmitUndefined(outer(Some(Null::Null)))).unwrap(),
+        r#"{"event":{"Renamed":{"name":"nulls"}},"nick":null}"#,
+    );
+}
+
+#[test]
+fn omit_undefined_rejects_undefined_it_cannot_leave_out() {
+    use nulls::serde::OmitUndefined;
+
+    let plain = Plain { name: Null::Null, message: message(), tags: vec![Null::Undefined] };
+    let err = serde_json::to_string(&OmitUndefined(&plain)).unwrap_err();
+    assert_eq!(err.to_string(), "`Undefined` can only be omitted from a struct field or map entry");
+
+    // Binary formats write struct fields by position, so nothing can be left out.
+    assert!(bincode::serialize(&OmitUndefined(message())).is_err());
+}
+
+#[test]
+fn policy_applies_to_binary_formats() {
+    use nulls::serde::{with_undefined_policy, ErrorOnUndefined, UndefinedPolicy};
+
+    assert!(bincode::serialize(&ErrorOnUndefined(message())).is_err());
+    assert!(with_undefined_policy(UndefinedPolicy::Error, || postcard::to_allocvec(&message())).is_err());
+
+    let defined = Message { age: Null::Null, ..message() };
+    let bytes = bincode::serialize(&ErrorOnUndefined(&defined)).unwrap();
+    assert_eq!(bincode::deserialize::<Message>(&bytes).unwrap(), defined);
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Tagged {
+    #[serde(default, with = "nulls::serde::tagged")]
+    name: Null<String>,
+}
+
+fn tagged() -> [Tagged; 3] {
+    [Tagged { name: Null::Undefined }, Tagged { name: Null::Null }, Tagged { name: nulls::new("nulls".into()) }]
+}
+
+#[test]
+fn tagged_msgpack_round_trip() {
+    for event in tagged() {
+        let bytes = rmp_serde::to_vec_named(&event).unwrap();
+        assert_eq!(rmp_serde::from_slice::<Tagged>(&bytes).unwrap(), event);
+
+        let bytes = rmp_serde::to_vec(&event).unwrap();
+        assert_eq!(rmp_serde::from_slice::<Tagged>(&bytes).unwrap(), event);
+    }
+}
+
+#[test]
+fn tagged_bincode_round_trip() {
+    for event in tagged() {
+        let bytes = bincode::serialize(&event).unwrap();
+        assert_eq!(bincode::deserialize::<Tagged>(&bytes).unwrap(), event);
+    }
+}
+
+#[test]
+fn borrowed_str() {
+    let value: Null<&str> = serde_json::from_str(r#""nulls""#).unwrap();
+    assert_eq!(value, nulls::new("nulls"));
+}
+
+#[test]
+fn deserializers_without_options() {
+    use serde::de::value::{BorrowedStrDeserializer, Error, UnitDeserializer};
+
+    let value = Null::<&str>::deserialize(BorrowedStrDeserializer::<Error>::new("nulls")).unwrap();
+    assert_eq!(value, nulls::new("nulls"));
+
+    let value = Null::<u32>::deserialize(UnitDeserializer::<Error>::new()).unwrap();
+    assert_eq!(value, Null::Null);
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Pair {
+    a: Null<u32>,
+    b: Null<u32>,
+}
+
+#[test]
+fn ron_round_trip() {
+    let pair = Pair { a: nulls::new(5), b: Null::Null };
+    let ron = ron::to_string(&pair).unwrap();
+
+    assert_eq!(ron, "(a:Some(5),b:None)");
+    assert_eq!(ron::from_str::<Pair>(&ron).unwrap(), pair);
+}
+
+mod aliased {
+    use nulls::Null;
+    use serde::{Deserialize, Serialize};
+
+    // An alias like this used to take the place of `Result` inside `with_inner!`.
+    #[allow(dead_code)]
+    type Result<T> = std::result::Result<T, String>;
+
+    mod cents {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_u64(value * 100)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+            u64::deserialize(deserializer).map(|cents| cents / 100)
+        }
+    }
+
+    nulls::with_inner!(pub mod null_cents, cents, u64);
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    pub struct Price {
+        #[serde(default, with = "null_cents")]
+        pub amount: Null<u64>,
+    }
+}
+
+#[test]
+fn with_inner_next_to_a_result_alias() {
+    use aliased::Price;
+
+    let price: Price = serde_json::from_str(r#"{"amount":1200}"#).unwrap();
+    assert_eq!(price, Price { amount: nulls::new(12) });
+    assert_eq!(serde_json::to_string(&price).unwrap(), r#"{"amount":1200}"#);
+}
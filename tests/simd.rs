@@ -0,0 +1,49 @@
+#![cfg(feature = "simd-json")]
+
+use nulls::Null;
+use serde::Deserialize;
+use simd_json::prelude::*;
+use simd_json::{json, OwnedValue};
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Patch {
+    #[serde(default)]
+    name: Null<String>,
+    #[serde(default)]
+    bio: Null<String>,
+    #[serde(default)]
+    age: Null<u32>,
+}
+
+#[test]
+fn from_slice_reads_all_three_states() {
+    let mut input = br#"{"name":"nulls","bio":null}"#.to_vec();
+    let patch: Patch = simd_json::serde::from_slice(&mut input).unwrap();
+    assert_eq!(patch, Patch { name: nulls::new("nulls".into()), bio: Null::Null, age: Null::Undefined });
+
+    assert_eq!(nulls::simd::from_slice::<u32>(&mut b"null".to_vec()).unwrap(), Null::Null);
+    assert_eq!(nulls::simd::from_slice::<u32>(&mut b"42".to_vec()).unwrap(), Null::Value(42));
+}
+
+#[test]
+fn value_round_trip() {
+    for null in [Null::Undefined, Null::Null, Null::Value(42u32)] {
+        let value = null.try_to_simd_value().unwrap();
+        assert_eq!(Null::<u32>::try_from_simd_value(value.as_ref()).unwrap(), null);
+    }
+
+    assert_eq!(Null::<u32>::Undefined.try_to_simd_value().unwrap(), None);
+    assert_eq!(Null::<u32>::Null.try_to_simd_value().unwrap(), Some(OwnedValue::from(())));
+    assert_eq!(Null::Value(42u32).try_to_simd_value().unwrap(), Some(json!(42)));
+}
+
+#[test]
+fn borrowed_value_reads_all_three_states() {
+    let mut input = br#"{"bio":null,"age":42}"#.to_vec();
+    let value = simd_json::to_borrowed_value(&mut input).unwrap();
+    let object = value.as_object().unwrap();
+
+    assert_eq!(Null::<u32>::try_from_simd_borrowed_value(object.get("name")).unwrap(), Null::Undefined);
+    assert_eq!(Null::<u32>::try_from_simd_borrowed_value(object.get("bio")).unwrap(), Null::Null);
+    assert_eq!(Null::<u32>::try_from_simd_borrowed_value(object.get("age")).unwrap(), Null::Value(42));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "sqlx-mysql")]
+
+//! Needs a MySQL server: `MYSQL_URL=mysql://... cargo test --features sqlx-mysql -- --ignored`.
+
+use nulls::Null;
+use sqlx::{Connection, MySqlConnection};
+
+async fn connect() -> MySqlConnection {
+    let url = std::env::var("MYSQL_URL").expect("MYSQL_URL is not set");
+    MySqlConnection::connect(&url).await.unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs a MySQL server at MYSQL_URL"]
+async fn round_trip() {
+    let mut conn = connect().await;
+
+    sqlx::query("CREATE TEMPORARY TABLE nulls_test (id INT PRIMARY KEY, name TEXT NULL, age INT NULL)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let rows = [
+        (1, nulls::new("nulls".to_string()), nulls::new(42)),
+        (2, Null::Null, Null::Null),
+        (3, Null::Undefined, Null::Undefined),
+    ];
+
+    for (id, name, age) in &rows {
+        sqlx::query("INSERT INTO nulls_test (id, name, age) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(name)
+            .bind(age)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    let read: Vec<(i32, Null<String>, Null<i32>)> = sqlx::query_as("SELECT id, name, age FROM nulls_test ORDER BY id")
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    // `Undefined` binds as NULL, so it reads back as `Null`.
+    assert_eq!(
+        read,
+        [(1, nulls::new("nulls".to_string()), nulls::new(42)), (2, Null::Null, Null::Null), (3, Null::Null, Null::Null)],
+    );
+}
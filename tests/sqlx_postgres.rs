@@ -0,0 +1,165 @@
+#![cfg(feature = "sqlx-postgres")]
+
+//! Needs a Postgres server: `DATABASE_URL=postgres://... cargo test -- --ignored`.
+
+use nulls::pg::CopyBinaryWriter;
+use nulls::Null;
+use sqlx::postgres::types::{PgInterval, PgRange};
+use sqlx::types::chrono::{DateTime, TimeZone, Utc};
+use sqlx::{Connection, PgConnection};
+
+async fn connect() -> PgConnection {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    PgConnection::connect(&url).await.unwrap()
+}
+
+#[derive(sqlx::Type, PartialEq, Debug)]
+#[sqlx(type_name = "nulls_mood", rename_all = "lowercase")]
+enum Mood {
+    Happy,
+    Sad,
+}
+
+#[derive(sqlx::Type, PartialEq, Debug)]
+#[sqlx(type_name = "nulls_pair")]
+struct Pair {
+    a: i32,
+    b: Null<String>,
+}
+
+type CustomRow = (i32, Null<Mood>, Null<Pair>, Vec<Null<Mood>>);
+
+#[tokio::test]
+#[ignore = "needs a Postgres server at DATABASE_URL"]
+async fn enum_and_composite_round_trip() {
+    let mut conn = connect().await;
+    let mut tx = conn.begin().await.unwrap();
+
+    sqlx::query("CREATE TYPE nulls_mood AS ENUM ('happy', 'sad')").execute(&mut *tx).await.unwrap();
+    sqlx::query("CREATE TYPE nulls_pair AS (a INT, b TEXT)").execute(&mut *tx).await.unwrap();
+    sqlx::query("CREATE TEMPORARY TABLE nulls_test (id INT PRIMARY KEY, mood nulls_mood, pair nulls_pair, moods nulls_mood[])")
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+    let rows = [
+        (1, nulls::new(Mood::Happy), nulls::new(Pair { a: 1, b: Null::Null }), vec![nulls::new(Mood::Sad), Null::Null]),
+        (2, Null::Null, Null::Null, vec![]),
+        (3, Null::Undefined, Null::Undefined, vec![Null::Undefined]),
+    ];
+
+    for (id, mood, pair, moods) in &rows {
+        sqlx::query("INSERT INTO nulls_test (id, mood, pair, moods) VALUES ($1, $2, $3, $4)")
+            .bind(id)
+            .bind(mood)
+            .bind(pair)
+            .bind(moods)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+    }
+
+    let read: Vec<CustomRow> = sqlx::query_as("SELECT id, mood, pair, moods FROM nulls_test ORDER BY id")
+        .fetch_all(&mut *tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        read,
+        [
+            (1, nulls::new(Mood::Happy), nulls::new(Pair { a: 1, b: Null::Null }), vec![nulls::new(Mood::Sad), Null::Null]),
+            (2, Null::Null, Null::Null, vec![]),
+            (3, Null::Null, Null::Null, vec![Null::Null]),
+        ],
+    );
+
+    tx.rollback().await.unwrap();
+}
+
+type RangeRow = (i32, Null<PgRange<DateTime<Utc>>>, Null<PgInterval>);
+
+#[tokio::test]
+#[ignore = "needs a Postgres server at DATABASE_URL"]
+async fn range_and_interval_round_trip() {
+    let mut conn = connect().await;
+
+    sqlx::query("CREATE TEMPORARY TABLE nulls_test (id INT PRIMARY KEY, during TSTZRANGE, every INTERVAL)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let start: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+    let during = PgRange::from(start..end);
+    let every = PgInterval { months: 1, days: 2, microseconds: 3 };
+
+    let rows = [
+        (1, nulls::new(during), nulls::new(every)),
+        (2, Null::Null, Null::Null),
+        (3, Null::Undefined, Null::Undefined),
+    ];
+
+    for (id, during, every) in &rows {
+        sqlx::query("INSERT INTO nulls_test (id, during, every) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(during)
+            .bind(every)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    let read: Vec<RangeRow> = sqlx::query_as("SELECT id, during, every FROM nulls_test ORDER BY id")
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(read, [(1, nulls::new(during), nulls::new(every)), (2, Null::Null, Null::Null), (3, Null::Null, Null::Null)]);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres server at DATABASE_URL"]
+async fn copy_binary_round_trip() {
+    let mut conn = connect().await;
+
+    sqlx::query("CREATE TEMPORARY TABLE nulls_test (id INT PRIMARY KEY, name TEXT)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let mut copy = CopyBinaryWriter::new();
+    copy.row().field(nulls::new(1)).unwrap().field(nulls::new("nulls")).unwrap();
+    copy.row().field(nulls::new(2)).unwrap().field(Null::<String>::Null).unwrap();
+    copy.row().field(nulls::new(3)).unwrap().field(Null::<String>::Undefined).unwrap();
+
+    let mut sink = conn.copy_in_raw("COPY nulls_test (id, name) FROM STDIN (FORMAT binary)").await.unwrap();
+    sink.send(copy.finish()).await.unwrap();
+    assert_eq!(sink.finish().await.unwrap(), 3);
+
+    let read: Vec<(i32, Null<String>)> = sqlx::query_as("SELECT id, name FROM nulls_test ORDER BY id")
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(read, [(1, nulls::new("nulls".to_string())), (2, Null::Null), (3, Null::Null)]);
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres server at DATABASE_URL"]
+async fn fetch_one_null() {
+    use nulls::FetchNullExt;
+
+    let mut conn = connect().await;
+
+    let value = sqlx::query_scalar::<_, i32>("SELECT NULL::INT").fetch_one_null(&mut conn).await.unwrap();
+    assert_eq!(value, Null::Null);
+
+    let value = sqlx::query_as::<_, (i32,)>("SELECT NULL::INT").fetch_one_null(&mut conn).await.unwrap();
+    assert_eq!(value, Null::Null);
+
+    let err = sqlx::query_scalar::<_, i32>("SELECT NULL::INT").fetch_null(&mut conn).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::ColumnDecode { .. }));
+
+    let err = sqlx::query_scalar::<_, i32>("SELECT 1 WHERE false").fetch_one_null(&mut conn).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::RowNotFound));
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "sqlx-sqlite")]
+
+use nulls::Null;
+use sqlx::{Connection, SqliteConnection};
+
+type Row = (i64, Null<String>, Null<i64>, Null<f64>, Null<Vec<u8>>);
+
+#[tokio::test]
+async fn round_trip() {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    sqlx::query("CREATE TABLE nulls_test (id INTEGER PRIMARY KEY, name TEXT, age INTEGER, score REAL, avatar BLOB)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let rows: [Row; 3] = [
+        (1, nulls::new("nulls".into()), nulls::new(42), nulls::new(0.5), nulls::new(vec![0xff, 0x00])),
+        (2, Null::Null, Null::Null, Null::Null, Null::Null),
+        (3, Null::Undefined, Null::Undefined, Null::Undefined, Null::Undefined),
+    ];
+
+    for (id, name, age, score, avatar) in &rows {
+        sqlx::query("INSERT INTO nulls_test (id, name, age, score, avatar) VALUES (?, ?, ?, ?, ?)")
+            .bind(id)
+            .bind(name)
+            .bind(age)
+            .bind(score)
+            .bind(avatar)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    let read: Vec<Row> = sqlx::query_as("SELECT id, name, age, score, avatar FROM nulls_test ORDER BY id")
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+
+    // `Undefined` binds as NULL, so it reads back as `Null`.
+    assert_eq!(read[0], rows[0]);
+    assert_eq!(read[1], rows[1]);
+    assert_eq!(read[2], (3, Null::Null, Null::Null, Null::Null, Null::Null));
+}
+
+#[tokio::test]
+async fn fetch_null() {
+    use nulls::FetchNullExt;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    let value = sqlx::query_scalar::<_, i64>("SELECT 7").fetch_null(&mut conn).await.unwrap();
+    assert_eq!(value, Null::Value(7));
+
+    let value = sqlx::query_scalar::<_, i64>("SELECT 7 WHERE 0").fetch_null(&mut conn).await.unwrap();
+    assert_eq!(value, Null::Null);
+}
+
+#[tokio::test]
+async fn fetch_one_null() {
+    use nulls::FetchNullExt;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    let value = sqlx::query_scalar::<_, i64>("SELECT 7").fetch_one_null(&mut conn).await.unwrap();
+    assert_eq!(value, Null::Value(7));
+
+    let err = sqlx::query_scalar::<_, i64>("SELECT 7 WHERE 0").fetch_one_null(&mut conn).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::RowNotFound));
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "surrealdb")]
+
+use nulls::Null;
+use surrealdb::sql::Value;
+
+type Row = (Null<String>, Null<i64>, Null<bool>);
+
+fn write((name, age, active): Row) -> [Value; 3] {
+    [name.into(), age.into(), active.into()]
+}
+
+fn read([name, age, active]: [Value; 3]) -> Row {
+    (
+        Null::try_from_surreal_value(name).unwrap(),
+        Null::try_from_surreal_value(age).unwrap(),
+        Null::try_from_surreal_value(active).unwrap(),
+    )
+}
+
+#[test]
+fn writes() {
+    assert_eq!(
+        write((nulls::new("nulls".into()), nulls::new(7), nulls::new(true))),
+        [Value::from("nulls"), Value::from(7_i64), Value::from(true)],
+    );
+    assert_eq!(write((Null::Null, Null::Null, Null::Null)), [Value::Null, Value::Null, Value::Null]);
+    assert_eq!(write((Null::Undefined, Null::Undefined, Null::Undefined)), [Value::None, Value::None, Value::None]);
+}
+
+#[test]
+fn round_trips() {
+    let rows: [Row; 3] = [
+        (nulls::new("nulls".into()), nulls::new(7), nulls::new(true)),
+        (Null::Null, Null::Null, Null::Null),
+        (Null::Undefined, Null::Undefined, Null::Undefined),
+    ];
+
+    // Unlike SQL backends, `NONE` keeps `Undefined` apart from `Null` on the way back.
+    assert_eq!(rows.clone().map(|row| read(write(row))), rows);
+}
+
+#[test]
+fn type_mismatch() {
+    assert!(Null::<i64>::try_from_surreal_value(Value::from("nulls")).is_err());
+    assert!(Null::<bool>::try_from_surreal_value(Value::from(7_i64)).is_err());
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "tiberius")]
+
+use nulls::Null;
+use tiberius::{ColumnData, FromSql, FromSqlOwned, IntoSql, ToSql};
+
+#[test]
+fn to_sql() {
+    assert_eq!(nulls::new(7_i32).to_sql(), ColumnData::I32(Some(7)));
+    assert_eq!(Null::<i32>::Null.to_sql(), ColumnData::I32(None));
+    assert_eq!(Null::<i32>::Undefined.to_sql(), ColumnData::I32(None));
+
+    assert_eq!(nulls::new("nulls".to_string()).into_sql(), ColumnData::String(Some("nulls".into())));
+    assert_eq!(Null::<String>::Null.into_sql(), ColumnData::String(None));
+    assert_eq!(Null::<String>::Undefined.into_sql(), ColumnData::String(None));
+}
+
+#[test]
+fn round_trips() {
+    let round_trip = |value: Null<i32>| Null::<i32>::from_sql(&value.into_sql()).unwrap();
+
+    assert_eq!(round_trip(nulls::new(7)), Some(nulls::new(7)));
+    assert_eq!(round_trip(Null::Null), Some(Null::Null));
+    assert_eq!(round_trip(Null::Undefined), Some(Null::Null));
+
+    let round_trip = |value: Null<String>| Null::<String>::from_sql_owned(value.into_sql()).unwrap();
+
+    assert_eq!(round_trip(nulls::new("nulls".into())), Some(nulls::new("nulls".into())));
+    assert_eq!(round_trip(Null::Null), Some(Null::Null));
+    assert_eq!(round_trip(Null::Undefined), Some(Null::Null));
+}
+
+#[test]
+fn type_mismatch() {
+    assert!(Null::<i32>::from_sql(&ColumnData::String(Some("nulls".into()))).is_err());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "tokio-postgres")]
+
+use bytes::BytesMut;
+use nulls::Null;
+use postgres_types::{FromSql, IsNull, ToSql, Type};
+
+fn round_trip<T>(value: &Null<T>, ty: &Type) -> Null<T>
+where
+    T: ToSql + for<'a> FromSql<'a>,
+{
+    let mut buffer = BytesMut::new();
+    let raw = match value.to_sql_checked(ty, &mut buffer).unwrap() {
+        IsNull::Yes => None,
+        IsNull::No => Some(&buffer[..]),
+    };
+
+    Null::<T>::from_sql_nullable(ty, raw).unwrap()
+}
+
+#[test]
+fn round_trips() {
+    assert_eq!(round_trip(&nulls::new("nulls".to_string()), &Type::TEXT), nulls::new("nulls".to_string()));
+    assert_eq!(round_trip(&Null::<String>::Null, &Type::TEXT), Null::Null);
+    assert_eq!(round_trip(&Null::<String>::Undefined, &Type::TEXT), Null::Null);
+
+    assert_eq!(round_trip(&nulls::new(7_i64), &Type::INT8), nulls::new(7));
+    assert_eq!(round_trip(&Null::<i64>::Null, &Type::INT8), Null::Null);
+    assert_eq!(round_trip(&Null::<i64>::Undefined, &Type::INT8), Null::Null);
+
+    assert_eq!(round_trip(&nulls::new(vec![1_u8, 2]), &Type::BYTEA), nulls::new(vec![1, 2]));
+    assert_eq!(round_trip(&Null::<Vec<u8>>::Null, &Type::BYTEA), Null::Null);
+}
+
+#[test]
+fn nested_value_is_null() {
+    // A `Value` whose inner value is itself NULL is read back as `Null`.
+    assert_eq!(round_trip(&nulls::new(None::<i32>), &Type::INT4), Null::Null);
+}
+
+#[test]
+fn accepts_what_the_inner_type_accepts() {
+    assert!(<Null<i32> as ToSql>::accepts(&Type::INT4));
+    assert!(!<Null<i32> as ToSql>::accepts(&Type::TEXT));
+    assert!(<Null<String> as FromSql>::accepts(&Type::TEXT));
+
+    let mut buffer = BytesMut::new();
+    assert!(nulls::new(7_i32).to_sql_checked(&Type::TEXT, &mut buffer).is_err());
+}